@@ -0,0 +1,925 @@
+//! Expansion of local library crates into the source code of a `bin`, so that the code can be
+//! submitted as a single file.
+
+use anyhow::{anyhow, Context as _};
+use proc_macro2::{LineColumn, Spacing, TokenStream, TokenTree};
+use std::{collections::HashSet, path::Path};
+use syn::{spanned::Spanned as _, Item, UseTree};
+
+/// A library crate to expand.
+#[derive(Debug)]
+pub(crate) struct Library<'a> {
+    /// Name used in the code, such as `our_lib`.
+    pub(crate) name: &'a str,
+    /// Path to the crate root.
+    pub(crate) src_path: &'a Path,
+}
+
+/// Expands `libs` into `code`.
+///
+/// - `mod foo;` in the libraries are inlined into `mod foo { .. }`.
+/// - Each library becomes `pub mod <name> { .. }` at the end of the code, and the paths are
+///   rewritten accordingly: `crate::` in the libraries becomes `crate::<name>::`, and `<name>::`
+///   becomes `crate::<name>::`.
+/// - Items with `#[cfg(test)]`, items of the libraries that are not referred to, and libraries
+///   that are not used at all are dropped.
+///
+/// If no library is used, `code` is returned as it is.
+pub(crate) fn bundle(code: &str, libs: &[Library<'_>]) -> anyhow::Result<String> {
+    let names = libs.iter().map(|l| l.name).collect::<HashSet<_>>();
+
+    let expanded = libs
+        .iter()
+        .map(|lib| expand_file(lib.src_path, lib.src_path.parent().unwrap()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let bin_tokens = parse_tokens(code)?;
+    let bin_idents = idents(&bin_tokens);
+
+    let mut bundled = vec![];
+    let mut exported_macros = vec![];
+
+    for (i, lib) in libs.iter().enumerate() {
+        // Identifiers in the other code that may refer to this library.
+        let mut user_idents = bin_idents.clone();
+        for (j, other) in expanded.iter().enumerate() {
+            if i != j {
+                user_idents.extend(idents(&parse_tokens(other)?));
+            }
+        }
+        if !user_idents.contains(lib.name) {
+            continue;
+        }
+
+        let glob_imported = has_glob_import(&bin_tokens, lib.name);
+        let (pruned, macros) = prune(&expanded[i], &user_idents, glob_imported)
+            .with_context(|| format!("could not expand `{}`", lib.src_path.display()))?;
+
+        bundled.push((lib.name, rewrite_paths(&pruned, Some(lib.name), &names)?));
+        exported_macros.extend(macros.into_iter().map(|m| (lib.name, m)));
+    }
+
+    if bundled.is_empty() {
+        return Ok(code.to_owned());
+    }
+
+    let mut acc = rewrite_bin(code, &names, &exported_macros)?;
+    if !acc.ends_with('\n') {
+        acc += "\n";
+    }
+    acc += "\n// The following code was expanded by `cargo compete bundle`.\n";
+    for (name, content) in bundled {
+        acc += &format!(
+            "\n#[allow(unused)]\npub mod {} {{\n{}\n}}\n",
+            name,
+            content.trim_end(),
+        );
+    }
+    Ok(acc)
+}
+
+/// Reads the file and inlines `mod foo;`s recursively.
+fn expand_file(path: &Path, dir: &Path) -> anyhow::Result<String> {
+    let code = crate::fs::read_to_string(path)?;
+    let file =
+        syn::parse_file(&code).with_context(|| format!("could not parse `{}`", path.display()))?;
+
+    let mut edits = vec![];
+    expand_mods(&code, &file.items, dir, &mut edits)?;
+    Ok(apply(&code, edits))
+}
+
+fn expand_mods(
+    code: &str,
+    items: &[Item],
+    dir: &Path,
+    edits: &mut Vec<(usize, usize, String)>,
+) -> anyhow::Result<()> {
+    for item in items {
+        if let Item::Mod(item_mod) = item {
+            if is_cfg_test(&item_mod.attrs) {
+                continue;
+            }
+
+            let name = item_mod.ident.to_string();
+            let dir = dir.join(&name);
+
+            match (&item_mod.content, item_mod.semi) {
+                (Some((_, items)), _) => expand_mods(code, items, &dir, edits)?,
+                (None, Some(semi)) => {
+                    let path = vec![dir.with_extension("rs"), dir.join("mod.rs")]
+                        .into_iter()
+                        .find(|p| p.exists())
+                        .with_context(|| {
+                            format!(
+                                "could not find the file for `mod {}` in `{}`",
+                                name,
+                                dir.display()
+                            )
+                        })?;
+
+                    let content = expand_file(&path, &dir)?;
+                    let (start, end) = (
+                        offset(code, semi.spans[0].start()),
+                        offset(code, semi.spans[0].end()),
+                    );
+                    edits.push((start, end, format!(" {{\n{}\n}}", content.trim_end())));
+                }
+                (None, None) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Drops `#[cfg(test)]` items and the items that neither `user_idents` nor the other kept items
+/// refer to. If `keep_all` is `true`, all of the top-level items are kept.
+///
+/// Items are matched by their names, so an item is kept if any of the kept code has an
+/// identifier of the same name. `use`s, macros, and `impl`s for kept types are always kept, as
+/// are the modules that contain such `impl`s.
+///
+/// Returns the result and the names of `#[macro_export]` macros in it.
+fn prune(
+    code: &str,
+    user_idents: &HashSet<String>,
+    keep_all: bool,
+) -> anyhow::Result<(String, Vec<String>)> {
+    let file = syn::parse_file(code)?;
+
+    let mut edits = vec![];
+    remove_cfg_test(code, &file.items, &mut edits);
+
+    let mut nodes = vec![];
+    collect_nodes(code, &file.items, None, &edits, &mut nodes)?;
+
+    let defined = nodes
+        .iter()
+        .flat_map(|node| match &node.kind {
+            NodeKind::Named(names) | NodeKind::Mod(names) => &names[..],
+            _ => &[],
+        })
+        .cloned()
+        .collect::<HashSet<_>>();
+
+    let mut kept = vec![false; nodes.len()];
+    let mut kept_idents = user_idents.clone();
+    loop {
+        let mut changed = false;
+
+        // Parents precede their children in `nodes`.
+        for (i, node) in nodes.iter().enumerate() {
+            if kept[i] || node.parent.map_or(false, |p| !kept[p]) {
+                continue;
+            }
+
+            let is_for_kept = |names: &[String]| {
+                names
+                    .iter()
+                    .filter(|n| defined.contains(*n))
+                    .all(|n| kept_idents.contains(n))
+            };
+
+            let keep = match &node.kind {
+                NodeKind::Named(_) | NodeKind::Mod(_) if keep_all && node.parent.is_none() => true,
+                NodeKind::Named(names) => names.iter().any(|n| kept_idents.contains(n)),
+                NodeKind::Mod(names) => {
+                    names.iter().any(|n| kept_idents.contains(n))
+                        || nodes[i + 1..].iter().enumerate().any(|(j, descendant)| {
+                            matches!(&descendant.kind, NodeKind::Impl(names) if is_for_kept(names))
+                                && is_descendant(&nodes, i + 1 + j, i)
+                        })
+                }
+                NodeKind::Impl(names) => is_for_kept(names),
+                NodeKind::Always => true,
+            };
+
+            if keep {
+                kept[i] = true;
+                kept_idents.extend(node.idents.iter().cloned());
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    for (i, node) in nodes.iter().enumerate() {
+        if !kept[i] && node.parent.map_or(true, |p| kept[p]) {
+            edits.push((node.span.0, node.span.1, "".to_owned()));
+        }
+    }
+
+    let pruned = apply(code, edits);
+    let macros = exported_macros(&pruned)?;
+    Ok((pruned, macros))
+}
+
+/// An item in a library, for [`prune`].
+struct Node {
+    /// Index of the `mod` that contains this item.
+    parent: Option<usize>,
+    kind: NodeKind,
+    /// Identifiers in the item. Empty for inline modules, whose items are nodes by themselves.
+    idents: HashSet<String>,
+    span: (usize, usize),
+}
+
+enum NodeKind {
+    /// Kept if any of the names are referred to.
+    Named(Vec<String>),
+    /// Inline modules, kept if any of the names are referred to or any of the `impl`s in them are
+    /// kept. They also provide the `#[macro_export]` macros in them.
+    Mod(Vec<String>),
+    /// `impl`s, kept if all of the types and the traits in the library that they are for are
+    /// referred to.
+    Impl(Vec<String>),
+    /// Kept as long as the parent is kept.
+    Always,
+}
+
+/// Whether `nodes[i]` is in the module `nodes[ancestor]`.
+fn is_descendant(nodes: &[Node], i: usize, ancestor: usize) -> bool {
+    let mut parent = nodes[i].parent;
+    while let Some(p) = parent {
+        if p == ancestor {
+            return true;
+        }
+        parent = nodes[p].parent;
+    }
+    false
+}
+
+fn collect_nodes(
+    code: &str,
+    items: &[Item],
+    parent: Option<usize>,
+    removed: &[(usize, usize, String)],
+    acc: &mut Vec<Node>,
+) -> anyhow::Result<()> {
+    for item in items {
+        let span = (
+            offset(code, item.span().start()),
+            offset(code, item.span().end()),
+        );
+        if removed.iter().any(|&(s, e, _)| (s, e) == span) {
+            continue;
+        }
+        let item_code = &code[span.0..span.1];
+
+        let named = |ident: &syn::Ident| NodeKind::Named(vec![ident.to_string()]);
+
+        let kind = match item {
+            Item::Mod(syn::ItemMod {
+                ident,
+                content: Some((_, items)),
+                ..
+            }) => {
+                let mut provides = exported_macros(item_code)?;
+                provides.push(ident.to_string());
+
+                acc.push(Node {
+                    parent,
+                    kind: NodeKind::Mod(provides),
+                    idents: HashSet::new(),
+                    span,
+                });
+                let parent = Some(acc.len() - 1);
+                collect_nodes(code, items, parent, removed, acc)?;
+                continue;
+            }
+            Item::Const(item) if item.ident != "_" => named(&item.ident),
+            Item::Enum(item) => named(&item.ident),
+            Item::Fn(item) => named(&item.sig.ident),
+            Item::Static(item) => named(&item.ident),
+            Item::Struct(item) => named(&item.ident),
+            Item::Trait(item) => named(&item.ident),
+            Item::TraitAlias(item) => named(&item.ident),
+            Item::Type(item) => named(&item.ident),
+            Item::Union(item) => named(&item.ident),
+            Item::Impl(item) => {
+                let mut names = vec![];
+                if let syn::Type::Path(ty) = &*item.self_ty {
+                    names.extend(ty.path.segments.last().map(|s| s.ident.to_string()));
+                }
+                if let Some((_, path, _)) = &item.trait_ {
+                    names.extend(path.segments.last().map(|s| s.ident.to_string()));
+                }
+                NodeKind::Impl(names)
+            }
+            _ => NodeKind::Always,
+        };
+
+        acc.push(Node {
+            parent,
+            kind,
+            idents: idents(&parse_tokens(item_code)?),
+            span,
+        });
+    }
+    Ok(())
+}
+
+fn remove_cfg_test(code: &str, items: &[Item], edits: &mut Vec<(usize, usize, String)>) {
+    for item in items {
+        let attrs = match item {
+            Item::Const(item) => &item.attrs,
+            Item::Enum(item) => &item.attrs,
+            Item::ExternCrate(item) => &item.attrs,
+            Item::Fn(item) => &item.attrs,
+            Item::Impl(item) => &item.attrs,
+            Item::Macro(item) => &item.attrs,
+            Item::Mod(item) => &item.attrs,
+            Item::Static(item) => &item.attrs,
+            Item::Struct(item) => &item.attrs,
+            Item::Trait(item) => &item.attrs,
+            Item::Type(item) => &item.attrs,
+            Item::Use(item) => &item.attrs,
+            _ => continue,
+        };
+
+        if is_cfg_test(attrs) {
+            let (start, end) = (
+                offset(code, item.span().start()),
+                offset(code, item.span().end()),
+            );
+            edits.push((start, end, "".to_owned()));
+        } else if let Item::Mod(syn::ItemMod {
+            content: Some((_, items)),
+            ..
+        }) = item
+        {
+            remove_cfg_test(code, items, edits);
+        }
+    }
+}
+
+/// Names of the `macro_rules!` with `#[macro_export]` in `code`.
+fn exported_macros(code: &str) -> anyhow::Result<Vec<String>> {
+    let mut acc = vec![];
+    find_exported_macros(&parse_tokens(code)?, &mut acc);
+    Ok(acc)
+}
+
+fn find_exported_macros(tts: &[TokenTree], acc: &mut Vec<String>) {
+    for (i, tt) in tts.iter().enumerate() {
+        if let TokenTree::Group(group) = tt {
+            let is_macro_export = matches!(
+                &*group.stream().into_iter().collect::<Vec<_>>(),
+                [TokenTree::Ident(ident)] if ident == "macro_export"
+            );
+            if is_macro_export {
+                // `#[macro_export] (#[..])* macro_rules! name`
+                let name = tts[i + 1..].windows(3).find_map(|w| match w {
+                    [TokenTree::Ident(kw), TokenTree::Punct(bang), TokenTree::Ident(name)]
+                        if kw == "macro_rules" && bang.as_char() == '!' =>
+                    {
+                        Some(name.to_string())
+                    }
+                    _ => None,
+                });
+                acc.extend(name);
+            } else {
+                find_exported_macros(&group.stream().into_iter().collect::<Vec<_>>(), acc);
+            }
+        }
+    }
+}
+
+/// Rewrites `crate::` into `crate::<this>::`, and `<lib>::` into `crate::<lib>::` for each of
+/// `libs`. Paths in macros are also rewritten.
+fn rewrite_paths(code: &str, this: Option<&str>, libs: &HashSet<&str>) -> anyhow::Result<String> {
+    let mut edits = vec![];
+    rewrite_paths_in(code, &parse_tokens(code)?, this, libs, &mut edits);
+    Ok(apply(code, edits))
+}
+
+fn rewrite_paths_in(
+    code: &str,
+    tts: &[TokenTree],
+    this: Option<&str>,
+    libs: &HashSet<&str>,
+    edits: &mut Vec<(usize, usize, String)>,
+) {
+    for (i, tt) in tts.iter().enumerate() {
+        match tt {
+            TokenTree::Group(group) => rewrite_paths_in(
+                code,
+                &group.stream().into_iter().collect::<Vec<_>>(),
+                this,
+                libs,
+                edits,
+            ),
+            TokenTree::Ident(ident) if is_path_sep(&tts[i + 1..]) => {
+                let follows_path_sep_or_dot = i > 0
+                    && matches!(&tts[i - 1], TokenTree::Punct(p) if p.as_char() == ':' || p.as_char() == '.');
+
+                if ident == "crate" {
+                    if let Some(this) = this {
+                        let end = offset(code, ident.span().end());
+                        edits.push((end, end, format!("::{}", this)));
+                    }
+                } else if libs.contains(&*ident.to_string()) && !follows_path_sep_or_dot {
+                    let start = offset(code, ident.span().start());
+                    edits.push((start, start, "crate::".to_owned()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rewrites the paths in the code of the `bin`, removing `extern crate`s of the libraries and
+/// `use`s of `#[macro_export]` macros.
+fn rewrite_bin(
+    code: &str,
+    libs: &HashSet<&str>,
+    exported_macros: &[(&str, String)],
+) -> anyhow::Result<String> {
+    let file = syn::parse_file(code)?;
+
+    let mut edits = vec![];
+    let mut replaced = vec![];
+
+    for item in &file.items {
+        let (start, end) = (
+            offset(code, item.span().start()),
+            offset(code, item.span().end()),
+        );
+
+        match item {
+            Item::ExternCrate(item) if libs.contains(&*item.ident.to_string()) => {
+                edits.push((start, end, "".to_owned()));
+                replaced.push((start, end));
+            }
+            // `#[macro_export]` macros are already at the crate root.
+            Item::Use(item_use) if uses_exported_macro(&item_use.tree, exported_macros) => {
+                edits.push((start, end, "".to_owned()));
+                replaced.push((start, end));
+            }
+            _ => {}
+        }
+    }
+
+    let mut path_edits = vec![];
+    rewrite_paths_in(code, &parse_tokens(code)?, None, libs, &mut path_edits);
+    edits.extend(
+        path_edits
+            .into_iter()
+            .filter(|&(pos, _, _)| !replaced.iter().any(|&(s, e)| s <= pos && pos < e)),
+    );
+
+    Ok(apply(code, edits))
+}
+
+/// Whether `tree` is `lib::a::b::name` where `name` is a `#[macro_export]` macro in `lib`.
+fn uses_exported_macro(tree: &UseTree, exported_macros: &[(&str, String)]) -> bool {
+    let lib = match tree {
+        UseTree::Path(path) => path.ident.to_string(),
+        _ => return false,
+    };
+
+    let mut tree = tree;
+    while let UseTree::Path(path) = tree {
+        tree = &path.tree;
+    }
+
+    matches!(
+        tree,
+        UseTree::Name(name)
+            if exported_macros.iter().any(|(l, m)| *l == lib && name.ident == m)
+    )
+}
+
+fn is_cfg_test(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path.is_ident("cfg") && attr.tokens.to_string().replace(' ', "") == "(test)"
+    })
+}
+
+/// Whether `tts` contains `lib::*`.
+fn has_glob_import(tts: &[TokenTree], lib: &str) -> bool {
+    tts.iter().enumerate().any(|(i, tt)| match tt {
+        TokenTree::Ident(ident) => {
+            ident == lib
+                && is_path_sep(&tts[i + 1..])
+                && matches!(tts.get(i + 3), Some(TokenTree::Punct(p)) if p.as_char() == '*')
+        }
+        TokenTree::Group(group) => {
+            has_glob_import(&group.stream().into_iter().collect::<Vec<_>>(), lib)
+        }
+        _ => false,
+    })
+}
+
+fn is_path_sep(tts: &[TokenTree]) -> bool {
+    matches!(
+        tts,
+        [TokenTree::Punct(p1), TokenTree::Punct(p2), ..]
+            if p1.as_char() == ':' && p1.spacing() == Spacing::Joint && p2.as_char() == ':'
+    )
+}
+
+/// Identifiers that appear in `code`, including the ones in macro calls.
+pub(crate) fn referenced_idents(code: &str) -> anyhow::Result<HashSet<String>> {
+    Ok(idents(&parse_tokens(code)?))
+}
+
+fn idents(tts: &[TokenTree]) -> HashSet<String> {
+    let mut acc = HashSet::new();
+    collect_idents(tts, &mut acc);
+    return acc;
+
+    fn collect_idents(tts: &[TokenTree], acc: &mut HashSet<String>) {
+        for tt in tts {
+            match tt {
+                TokenTree::Ident(ident) => {
+                    acc.insert(ident.to_string());
+                }
+                TokenTree::Group(group) => {
+                    collect_idents(&group.stream().into_iter().collect::<Vec<_>>(), acc)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn parse_tokens(code: &str) -> anyhow::Result<Vec<TokenTree>> {
+    let tts = code
+        .parse::<TokenStream>()
+        .map_err(|e| anyhow!("could not tokenize the code: {:?}", e))?;
+    Ok(tts.into_iter().collect())
+}
+
+/// Converts a [`LineColumn`] of a span into a byte offset in `code`.
+fn offset(code: &str, LineColumn { line, column }: LineColumn) -> usize {
+    let line_start = code
+        .split('\n')
+        .take(line - 1)
+        .map(|l| l.len() + 1)
+        .sum::<usize>();
+    let rest = &code[line_start..];
+    line_start
+        + rest
+            .char_indices()
+            .nth(column)
+            .map_or(rest.len(), |(i, _)| i)
+}
+
+/// Applies `(start, end, replacement)`s. The ranges must not overlap.
+fn apply(code: &str, mut edits: Vec<(usize, usize, String)>) -> String {
+    edits.sort_by_key(|&(start, end, _)| (start, end));
+
+    let mut acc = "".to_owned();
+    let mut pos = 0;
+    for (start, end, replacement) in edits {
+        if start < pos {
+            continue;
+        }
+        acc += &code[pos..start];
+        acc += &replacement;
+        pos = end;
+    }
+    acc += &code[pos..];
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Library;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bundle() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-test-bundle-")
+            .tempdir()?;
+        let src = tempdir.path().join("src");
+        crate::fs::create_dir_all(&src)?;
+
+        crate::fs::write(
+            src.join("lib.rs"),
+            r#"pub mod fenwick;
+mod util;
+pub mod unused;
+
+#[macro_export]
+macro_rules! chmax {
+    ($a:expr, $b:expr) => {
+        if $a < $b {
+            $a = $b;
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {}
+"#,
+        )?;
+        crate::fs::write(
+            src.join("fenwick.rs"),
+            r#"use crate::util::lowbit;
+
+pub struct Fenwick(pub Vec<i64>);
+
+impl Fenwick {
+    pub fn add(&mut self, mut i: usize, x: i64) {
+        while i < self.0.len() {
+            self.0[i] += x;
+            i += lowbit(i);
+        }
+    }
+}
+"#,
+        )?;
+        crate::fs::write(
+            src.join("util.rs"),
+            r#"pub(crate) fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+pub(crate) struct Unused;
+
+impl Unused {
+    pub(crate) fn f() {}
+}
+"#,
+        )?;
+        crate::fs::write(src.join("unused.rs"), "pub fn f() {}\n")?;
+
+        let code = r#"use our_lib::chmax;
+use our_lib::fenwick::Fenwick;
+
+fn main() {
+    let mut x = 1;
+    chmax!(x, 2);
+    Fenwick(vec![0; 4]).add(1, x);
+}
+"#;
+
+        let bundled = super::bundle(
+            code,
+            &[Library {
+                name: "our_lib",
+                src_path: &src.join("lib.rs"),
+            }],
+        )?;
+
+        assert_eq!(
+            r#"
+use crate::our_lib::fenwick::Fenwick;
+
+fn main() {
+    let mut x = 1;
+    chmax!(x, 2);
+    Fenwick(vec![0; 4]).add(1, x);
+}
+
+// The following code was expanded by `cargo compete bundle`.
+
+#[allow(unused)]
+pub mod our_lib {
+pub mod fenwick {
+use crate::our_lib::util::lowbit;
+
+pub struct Fenwick(pub Vec<i64>);
+
+impl Fenwick {
+    pub fn add(&mut self, mut i: usize, x: i64) {
+        while i < self.0.len() {
+            self.0[i] += x;
+            i += lowbit(i);
+        }
+    }
+}
+}
+mod util {
+pub(crate) fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+
+
+
+}
+
+
+#[macro_export]
+macro_rules! chmax {
+    ($a:expr, $b:expr) => {
+        if $a < $b {
+            $a = $b;
+        }
+    };
+}
+}
+"#,
+            bundled,
+        );
+
+        tempdir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn bundle_shadowed_names() -> anyhow::Result<()> {
+        // Items are kept by names, so the ones shadowed in the `bin` are kept too.
+        let bundled = bundle_lib(
+            r#"pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+pub fn digits(n: u64) -> usize {
+    n.to_string().len()
+}
+
+pub fn unused() {}
+"#,
+            r#"use our_lib::lcm;
+
+fn main() {
+    let digits = 1;
+    println!("{}", lcm(2, 3) + digits);
+}
+"#,
+        )?;
+
+        assert_eq!(
+            r#"use crate::our_lib::lcm;
+
+fn main() {
+    let digits = 1;
+    println!("{}", lcm(2, 3) + digits);
+}
+
+// The following code was expanded by `cargo compete bundle`.
+
+#[allow(unused)]
+pub mod our_lib {
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+pub fn digits(n: u64) -> usize {
+    n.to_string().len()
+}
+}
+"#,
+            bundled,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bundle_macros() -> anyhow::Result<()> {
+        let bundled = bundle_lib(
+            r#"pub mod internal {
+    pub fn read() -> u32 {
+        0
+    }
+}
+
+pub mod unused {
+    pub fn f() {}
+}
+
+#[macro_export]
+macro_rules! input {
+    () => {
+        $crate::internal::read()
+    };
+}
+"#,
+            r#"use our_lib::input;
+
+fn main() {
+    let n = input!();
+    println!("{}", n);
+}
+"#,
+        )?;
+
+        assert_eq!(
+            r#"
+
+fn main() {
+    let n = input!();
+    println!("{}", n);
+}
+
+// The following code was expanded by `cargo compete bundle`.
+
+#[allow(unused)]
+pub mod our_lib {
+pub mod internal {
+    pub fn read() -> u32 {
+        0
+    }
+}
+
+
+
+#[macro_export]
+macro_rules! input {
+    () => {
+        $crate::our_lib::internal::read()
+    };
+}
+}
+"#,
+            bundled,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bundle_reexports() -> anyhow::Result<()> {
+        // `impl`s in modules that are not referred to by name are kept with the modules.
+        let bundled = bundle_lib(
+            r#"pub use self::inner::Fenwick as Bit;
+
+mod inner {
+    pub struct Fenwick(pub Vec<i64>);
+}
+
+mod impls {
+    impl crate::inner::Fenwick {
+        pub fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+}
+
+pub mod unused {
+    pub fn f() {}
+}
+"#,
+            r#"use our_lib::Bit;
+
+fn main() {
+    println!("{}", Bit(vec![0; 4]).len());
+}
+"#,
+        )?;
+
+        assert_eq!(
+            r#"use crate::our_lib::Bit;
+
+fn main() {
+    println!("{}", Bit(vec![0; 4]).len());
+}
+
+// The following code was expanded by `cargo compete bundle`.
+
+#[allow(unused)]
+pub mod our_lib {
+pub use self::inner::Fenwick as Bit;
+
+mod inner {
+    pub struct Fenwick(pub Vec<i64>);
+}
+
+mod impls {
+    impl crate::our_lib::inner::Fenwick {
+        pub fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+}
+}
+"#,
+            bundled,
+        );
+        Ok(())
+    }
+
+    fn bundle_lib(lib_rs: &str, code: &str) -> anyhow::Result<String> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-test-bundle-")
+            .tempdir()?;
+        let lib_rs_path = tempdir.path().join("lib.rs");
+        crate::fs::write(&lib_rs_path, lib_rs)?;
+
+        let bundled = super::bundle(
+            code,
+            &[Library {
+                name: "our_lib",
+                src_path: &lib_rs_path,
+            }],
+        )?;
+
+        tempdir.close()?;
+        Ok(bundled)
+    }
+}
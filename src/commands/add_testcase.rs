@@ -0,0 +1,138 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+    testing::OutputSource,
+};
+use anyhow::Context as _;
+use std::{io::Read as _, path::PathBuf};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteAddTestcase {
+    /// Name of the test case. Defaults to `custom<N>`
+    #[structopt(long, value_name("NAME"))]
+    pub name: Option<String>,
+
+    /// Path to the input. Reads from stdin if omitted
+    #[structopt(long, value_name("PATH"))]
+    pub input: Option<PathBuf>,
+
+    /// Path to the expected output
+    #[structopt(long, value_name("PATH"), conflicts_with_all(&["edit", "with"]))]
+    pub output: Option<PathBuf>,
+
+    /// Write the expected output with `$VISUAL` or `$EDITOR`
+    #[structopt(long, conflicts_with("with"))]
+    pub edit: bool,
+
+    /// Bin target that is trusted to be correct. Its output is saved as the expected output
+    #[structopt(long, value_name("NAME"))]
+    pub with: Option<String>,
+
+    /// Package (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Build the `--with` bin in release mode, with optimizations
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Problem Index
+    pub problem: String,
+}
+
+pub(crate) fn run(opt: OptCompeteAddTestcase, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteAddTestcase {
+        name,
+        input,
+        output,
+        edit,
+        with,
+        package,
+        release,
+        manifest_path,
+        color,
+        problem,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let input = input.map(|p| cwd.join(p));
+    let output = output.map(|p| cwd.join(p));
+
+    let manifest_path = manifest_path
+        .map(Ok)
+        .unwrap_or_else(|| crate::project::locate_project(cwd))?;
+    let metadata = crate::project::cargo_metadata(&manifest_path)?;
+
+    let cargo_compete_config = metadata.read_compete_toml()?;
+
+    let member = metadata.query_for_member(package.as_deref())?;
+
+    let package_metadata_bin = member
+        .read_package_metadata()?
+        .bin
+        .remove(&problem)
+        .with_context(|| {
+            format!(
+                "could not find `{}` in `package.metadata.cargo-compete.bin`",
+                problem
+            )
+        })?;
+
+    let input = if let Some(input) = input {
+        crate::fs::read_to_string(input)?
+    } else {
+        if atty::is(atty::Stream::Stdin) {
+            shell.status("Reading", "the input from stdin. press Ctrl-D to finish")?;
+        }
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .with_context(|| "could not read the input from stdin")?;
+        input
+    };
+
+    let output = if let Some(output) = &output {
+        OutputSource::File(output)
+    } else if edit {
+        OutputSource::Editor
+    } else if let Some(with) = &with {
+        OutputSource::Bin(with)
+    } else {
+        OutputSource::None
+    };
+
+    crate::testing::add_testcase(crate::testing::AddTestcaseArgs {
+        metadata: &metadata,
+        member,
+        cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+        package_metadata_bin: &package_metadata_bin,
+        name,
+        input,
+        output,
+        default_match: cargo_compete_config.test.default_match,
+        release,
+        shell,
+    })
+}
@@ -0,0 +1,101 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+};
+use anyhow::Context as _;
+use std::{num::NonZeroUsize, path::PathBuf};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteBench {
+    /// Bin target or Git revision to compare with
+    #[structopt(long, value_name("BIN|REV"))]
+    pub against: String,
+
+    /// Number of runs for each input
+    #[structopt(long, value_name("N"), default_value("10"))]
+    pub runs: NonZeroUsize,
+
+    /// Bin target that generates inputs, instead of the test cases. It receives the seed as an
+    /// argument
+    #[structopt(long, value_name("NAME"))]
+    pub generator: Option<String>,
+
+    /// Number of inputs to generate
+    #[structopt(long, value_name("N"), default_value("5"))]
+    pub inputs: NonZeroUsize,
+
+    /// Package (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Problem Index
+    pub problem: String,
+}
+
+pub(crate) fn run(opt: OptCompeteBench, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteBench {
+        against,
+        runs,
+        generator,
+        inputs,
+        package,
+        manifest_path,
+        color,
+        problem,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(Ok)
+        .unwrap_or_else(|| crate::project::locate_project(cwd))?;
+    let metadata = crate::project::cargo_metadata(&manifest_path)?;
+
+    let cargo_compete_config = metadata.read_compete_toml()?;
+
+    let member = metadata.query_for_member(package.as_deref())?;
+
+    let package_metadata_bin = member
+        .read_package_metadata()?
+        .bin
+        .remove(&problem)
+        .with_context(|| {
+            format!(
+                "could not find `{}` in `package.metadata.cargo-compete.bin`",
+                problem
+            )
+        })?;
+
+    crate::testing::bench(crate::testing::BenchArgs {
+        metadata: &metadata,
+        member,
+        cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+        package_metadata_bin: &package_metadata_bin,
+        against: &against,
+        runs: runs.get(),
+        generator: generator.as_deref(),
+        inputs: inputs.get(),
+        shell,
+    })
+}
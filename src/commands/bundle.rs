@@ -0,0 +1,77 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+};
+use anyhow::Context as _;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteBundle {
+    /// Package (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Problem Index
+    pub problem: String,
+}
+
+pub(crate) fn run(opt: OptCompeteBundle, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteBundle {
+        package,
+        manifest_path,
+        color,
+        problem,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(Ok)
+        .unwrap_or_else(|| crate::project::locate_project(cwd))?;
+    let metadata = crate::project::cargo_metadata(&manifest_path)?;
+
+    let member = metadata.query_for_member(package.as_deref())?;
+
+    let package_metadata_bin = member
+        .read_package_metadata()?
+        .bin
+        .remove(&problem)
+        .with_context(|| {
+            format!(
+                "could not find `{}` in `package.metadata.cargo-compete.bin`",
+                problem
+            )
+        })?;
+
+    let bin = member.bin_target(&package_metadata_bin.name)?;
+
+    let code = crate::source_code::bundle(&metadata, member, bin)?;
+    crate::source_code::check(
+        &metadata, member, &bin.name, &code, true, None, false, shell,
+    )?;
+
+    write!(shell.out(), "{}", code)?;
+    shell.out().flush()?;
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use crate::{
+    history::{History, LocalTest, Record},
+    shell::ColorChoice,
+};
+use prettytable::{cell, row};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteHistory {
+    /// Show only the submissions for the problem
+    #[structopt(long, value_name("INDEX"))]
+    pub problem: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    #[structopt(subcommand)]
+    pub show: Option<OptCompeteHistoryShow>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum OptCompeteHistoryShow {
+    /// Print the code sent in a submission
+    #[structopt(author)]
+    Show {
+        /// ID in the history
+        id: u64,
+    },
+}
+
+pub(crate) fn run(opt: OptCompeteHistory, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteHistory {
+        problem,
+        manifest_path,
+        color,
+        show,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(Ok)
+        .unwrap_or_else(|| crate::project::locate_project(cwd))?;
+    let metadata = crate::project::cargo_metadata(&manifest_path)?;
+
+    let history = History::load(&metadata.workspace_root)?;
+
+    if let Some(OptCompeteHistoryShow::Show { id }) = show {
+        write!(shell.out(), "{}", history.source(id)?)?;
+        shell.out().flush()?;
+        return Ok(());
+    }
+
+    let mut table = crate::shell::table();
+
+    table.set_titles(row![
+        "ID",
+        "Submitted at",
+        "Contest",
+        "Problem",
+        "Test",
+        "Verdict",
+        "Commit",
+        "URL",
+    ]);
+
+    let records = history
+        .records
+        .iter()
+        .filter(|r| problem.as_ref().map_or(true, |p| *p == r.problem));
+
+    for Record {
+        id,
+        submitted_at,
+        contest,
+        problem,
+        submission_url,
+        git_commit,
+        git_dirty,
+        local_test,
+        verdict,
+        ..
+    } in records
+    {
+        table.add_row(row![
+            id,
+            submitted_at.format("%F %T"),
+            contest.as_deref().unwrap_or("-"),
+            problem,
+            match local_test {
+                LocalTest::Passed => "Passed",
+                LocalTest::Skipped => "Skipped",
+            },
+            verdict.as_deref().unwrap_or("-"),
+            match git_commit {
+                Some(commit) => format!(
+                    "{}{}",
+                    &commit[..commit.len().min(7)],
+                    if *git_dirty { "-dirty" } else { "" },
+                ),
+                None => "-".to_owned(),
+            },
+            submission_url,
+        ]);
+    }
+
+    write!(shell.out(), "{}", table)?;
+    shell.out().flush()?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::shell::ColorChoice;
+use prettytable::{cell, row};
+use snowchains_core::web::PlatformKind;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteLanguages {
+    /// Contest ID to find the submit form. Required for Codeforces
+    #[structopt(long, value_name("CONTEST_ID"))]
+    pub contest: Option<String>,
+
+    /// Problem index to find the submit form. Used with `--contest` for AtCoder
+    #[structopt(long, value_name("INDEX"), requires("contest"))]
+    pub problem: Option<String>,
+
+    /// Print the list saved by the last run instead of retrieving it
+    #[structopt(long)]
+    pub offline: bool,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Platform
+    #[structopt(possible_values(PlatformKind::KEBAB_CASE_VARIANTS))]
+    pub platform: PlatformKind,
+}
+
+pub(crate) fn run(opt: OptCompeteLanguages, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteLanguages {
+        contest,
+        problem,
+        offline,
+        color,
+        platform,
+    } = opt;
+
+    let crate::Context {
+        cwd: _,
+        cookies_path,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let names_by_id = if offline {
+        crate::web::languages::read_cache(platform)?
+    } else {
+        let names_by_id = crate::web::languages::retrieve(
+            platform,
+            contest.as_deref(),
+            problem.as_deref(),
+            &cookies_path,
+            shell,
+        )?;
+        crate::web::languages::write_cache(platform, &names_by_id, shell)?;
+        names_by_id
+    };
+
+    let mut table = crate::shell::table();
+
+    table.set_titles(row!["ID", "Name"]);
+
+    for (id, name) in &names_by_id {
+        table.add_row(row![id, name]);
+    }
+
+    write!(shell.out(), "{}", table)?;
+    shell.out().flush()?;
+    Ok(())
+}
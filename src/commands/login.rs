@@ -2,11 +2,8 @@ use crate::{
     shell::{ColorChoice, Shell},
     web::credentials,
 };
-use snowchains_core::web::{
-    Atcoder, AtcoderLoginCredentials, Codeforces, CodeforcesLoginCredentials, CookieStorage, Login,
-    LoginOutcome, PlatformKind,
-};
-use std::{borrow::BorrowMut as _, cell::RefCell, io};
+use snowchains_core::web::{LoginOutcome, PlatformKind};
+use std::io;
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use termcolor::Color;
@@ -38,52 +35,13 @@ pub(crate) fn run(opt: OptCompeteLogin, ctx: crate::Context<'_>) -> anyhow::Resu
 
     shell.set_color_choice(color);
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-    let timeout = crate::web::TIMEOUT;
-
     match platform {
         PlatformKind::Atcoder => {
-            let outcome = {
-                let shell = RefCell::new(shell.borrow_mut());
-
-                let credentials = AtcoderLoginCredentials {
-                    username_and_password: &mut credentials::username_and_password(
-                        &shell,
-                        "Username: ",
-                        "Password: ",
-                    ),
-                };
-
-                Atcoder::exec(Login {
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
-                })?
-            };
-
+            let outcome = crate::web::session::login(platform, &cookies_path, shell)?;
             status(shell, outcome)?;
         }
         PlatformKind::Codeforces => {
-            let outcome = {
-                let shell = RefCell::new(shell.borrow_mut());
-
-                let credentials = CodeforcesLoginCredentials {
-                    username_and_password: &mut credentials::username_and_password(
-                        &shell,
-                        "Handle/Email: ",
-                        "Password: ",
-                    ),
-                };
-
-                Codeforces::exec(Login {
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
-                })?
-            };
-
+            let outcome = crate::web::session::login(platform, &cookies_path, shell)?;
             status(shell, outcome)?;
 
             let (api_key, api_secret) = credentials::codeforces_api_key_and_secret(shell)?;
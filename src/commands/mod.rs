@@ -1,11 +1,19 @@
+pub(crate) mod add_testcase;
+pub(crate) mod bench;
+pub(crate) mod bundle;
+pub(crate) mod history;
 pub(crate) mod init;
+pub(crate) mod languages;
 pub(crate) mod login;
 pub(crate) mod migrate_cargo_atcoder;
 pub(crate) mod new;
 pub(crate) mod open;
 pub(crate) mod participate;
+pub(crate) mod retrieve_submission;
 pub(crate) mod retrieve_submission_summaries;
 pub(crate) mod retrieve_testcases;
+pub(crate) mod shrink;
+pub(crate) mod stress;
 pub(crate) mod submit;
 pub(crate) mod test;
 pub(crate) mod watch_submissions;
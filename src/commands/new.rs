@@ -1,5 +1,7 @@
 use crate::{project::MetadataExt as _, shell::ColorChoice};
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
+use cargo_metadata::Metadata;
+use heck::KebabCase as _;
 use snowchains_core::web::{
     PlatformKind, RetrieveTestCasesOutcome, RetrieveTestCasesOutcomeContest,
     RetrieveTestCasesOutcomeProblem,
@@ -23,6 +25,14 @@ pub struct OptCompeteNew {
     #[structopt(long, value_name("INDEX"))]
     pub problems: Option<Vec<String>>,
 
+    /// Add the problems to the existing package, skipping the ones it already has
+    #[structopt(long)]
+    pub append: bool,
+
+    /// Do not retrieve the problem pages for the memory limits and the floating-point tolerances
+    #[structopt(long)]
+    pub no_problem_pages: bool,
+
     /// Path to Cargo.toml
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
@@ -45,6 +55,8 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
         full,
         open,
         problems,
+        append,
+        no_problem_pages,
         manifest_path,
         color,
         contest,
@@ -69,7 +81,7 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let contest = contest.with_context(|| "`contest` is required for AtCoder")?;
             let problems = problems.map(|ps| ps.into_iter().collect());
 
-            let outcome = crate::web::retrieve_testcases::dl_from_atcoder(
+            let mut outcome = crate::web::retrieve_testcases::dl_from_atcoder(
                 &contest,
                 problems,
                 full,
@@ -83,6 +95,10 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
                 .map(|RetrieveTestCasesOutcomeContest { id, .. }| id)
                 .unwrap_or(&contest);
 
+            if append {
+                retain_new_problems(&metadata, package_name, &mut outcome.problems)?;
+            }
+
             let problems = outcome
                 .problems
                 .iter()
@@ -93,13 +109,21 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let pkg_manifest_dir = metadata.workspace_root.join(package_name);
             let urls = urls(&outcome);
 
+            let src_paths = if append {
+                metadata.append_to_member(package_name, &problems, false, shell)?
+            } else {
+                metadata.add_member(package_name, &problems, false, shell)?
+            };
+
             let file_paths = itertools::zip_eq(
-                metadata.add_member(package_name, &problems, false, shell)?,
+                src_paths,
                 crate::web::retrieve_testcases::save_test_cases(
                     &workspace_root,
                     &pkg_manifest_dir.to_str().expect("this is from JSON"),
                     &cargo_compete_config.test_suite,
                     outcome,
+                    !no_problem_pages,
+                    &cookies_path,
                     shell,
                 )?,
             )
@@ -120,7 +144,7 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let contest = contest.with_context(|| "`contest` is required for Codeforces")?;
             let problems = problems.map(|ps| ps.into_iter().collect());
 
-            let outcome = crate::web::retrieve_testcases::dl_from_codeforces(
+            let mut outcome = crate::web::retrieve_testcases::dl_from_codeforces(
                 &contest,
                 problems,
                 &cookies_path,
@@ -133,6 +157,10 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
                 .map(|RetrieveTestCasesOutcomeContest { id, .. }| id)
                 .unwrap_or(&contest);
 
+            if append {
+                retain_new_problems(&metadata, package_name, &mut outcome.problems)?;
+            }
+
             let problems = outcome
                 .problems
                 .iter()
@@ -143,13 +171,21 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let pkg_manifest_dir = metadata.workspace_root.join(package_name);
             let urls = urls(&outcome);
 
+            let src_paths = if append {
+                metadata.append_to_member(package_name, &problems, false, shell)?
+            } else {
+                metadata.add_member(package_name, &problems, false, shell)?
+            };
+
             let file_paths = itertools::zip_eq(
-                metadata.add_member(package_name, &problems, false, shell)?,
+                src_paths,
                 crate::web::retrieve_testcases::save_test_cases(
                     &workspace_root,
                     &pkg_manifest_dir.to_str().expect("this is from JSON"),
                     &cargo_compete_config.test_suite,
                     outcome,
+                    !no_problem_pages,
+                    &cookies_path,
                     shell,
                 )?,
             )
@@ -170,7 +206,7 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let contest = contest.as_deref();
             let problems = problems.map(|ps| ps.into_iter().collect());
 
-            let outcome =
+            let mut outcome =
                 crate::web::retrieve_testcases::dl_from_yukicoder(contest, problems, full, shell)?;
 
             let package_name = outcome
@@ -181,6 +217,10 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let is_no = package_name.is_none();
             let package_name = package_name.unwrap_or("problems");
 
+            if append {
+                retain_new_problems(&metadata, package_name, &mut outcome.problems)?;
+            }
+
             let problems = outcome
                 .problems
                 .iter()
@@ -191,13 +231,21 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
             let pkg_manifest_dir = metadata.workspace_root.join(package_name);
             let urls = urls(&outcome);
 
+            let src_paths = if append {
+                metadata.append_to_member(package_name, &problems, is_no, shell)?
+            } else {
+                metadata.add_member(package_name, &problems, is_no, shell)?
+            };
+
             let file_paths = itertools::zip_eq(
-                metadata.add_member(package_name, &problems, is_no, shell)?,
+                src_paths,
                 crate::web::retrieve_testcases::save_test_cases(
                     &workspace_root,
                     &pkg_manifest_dir.to_str().expect("this is from JSON"),
                     &cargo_compete_config.test_suite,
                     outcome,
+                    !no_problem_pages,
+                    &cookies_path,
                     shell,
                 )?,
             )
@@ -218,6 +266,22 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Removes the problems that the existing package already has.
+fn retain_new_problems(
+    metadata: &Metadata,
+    package_name: &str,
+    problems: &mut Vec<RetrieveTestCasesOutcomeProblem>,
+) -> anyhow::Result<()> {
+    let existing = metadata.bin_indexes_of(package_name)?;
+    problems.retain(|RetrieveTestCasesOutcomeProblem { index, .. }| {
+        !existing.contains(&index.to_kebab_case())
+    });
+    if problems.is_empty() {
+        bail!("`{}` already has all of the problems", package_name);
+    }
+    Ok(())
+}
+
 fn urls(outcome: &RetrieveTestCasesOutcome) -> Vec<Url> {
     outcome.problems.iter().map(|p| p.url.clone()).collect()
 }
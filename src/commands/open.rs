@@ -95,6 +95,7 @@ pub(crate) fn run(opt: OptCompeteOpen, ctx: crate::Context<'_>) -> anyhow::Resul
             &mut package_metadata_bin,
             Some(&missing),
             full,
+            true,
             &metadata.workspace_root,
             &cargo_compete_config.test_suite,
             &cookies_path,
@@ -0,0 +1,237 @@
+use crate::{
+    history::History,
+    project::{MetadataExt as _, PackageExt as _, TargetProblem},
+    shell::ColorChoice,
+};
+use anyhow::{bail, Context as _};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteRetrieveSubmission {
+    /// Retrieve the latest submission for the problem instead
+    #[structopt(
+        long,
+        value_name("PROBLEM"),
+        conflicts_with("submission"),
+        required_unless("submission")
+    )]
+    pub latest_for: Option<String>,
+
+    /// Write the code to the `src_path` of the problem. Also used to resolve a submission ID
+    #[structopt(long, value_name("PROBLEM"))]
+    pub problem: Option<String>,
+
+    /// Write the code to the file instead
+    #[structopt(short, long, value_name("PATH"), conflicts_with("problem"))]
+    pub output: Option<PathBuf>,
+
+    /// Package (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// URL or ID of the submission
+    pub submission: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptCompeteRetrieveSubmission,
+    ctx: crate::Context<'_>,
+) -> anyhow::Result<()> {
+    let OptCompeteRetrieveSubmission {
+        latest_for,
+        problem,
+        output,
+        package,
+        manifest_path,
+        color,
+        submission,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let output = output.map(|p| cwd.join(p));
+
+    let manifest_path = manifest_path
+        .map(Ok)
+        .unwrap_or_else(|| crate::project::locate_project(cwd))?;
+    let metadata = crate::project::cargo_metadata(&manifest_path)?;
+
+    let member = metadata.query_for_member(package.as_deref())?;
+    let mut package_metadata = member.read_package_metadata()?;
+
+    // `--latest-for` also writes to the `src_path` unless `--output` is given.
+    let problem = problem.or_else(|| latest_for.clone().filter(|_| output.is_none()));
+
+    let url = if let Some(latest_for) = &latest_for {
+        let target = &package_metadata
+            .bin
+            .get(latest_for)
+            .with_context(|| format!("could not find `{}`", latest_for))?
+            .problem;
+
+        match target {
+            TargetProblem::Atcoder { contest, url, .. } => {
+                let task_url = url
+                    .as_ref()
+                    .with_context(|| format!("`url` is missing for `{}`", latest_for))?;
+                crate::web::retrieve_submission::latest_atcoder_submission(
+                    contest,
+                    task_url,
+                    &cookies_path,
+                    shell,
+                )?
+            }
+            TargetProblem::Codeforces { contest, index, .. } => {
+                // The task URLs end with the indexes.
+                crate::web::submission_summaries::retrieve_codeforces(contest, shell)?
+                    .summaries
+                    .into_iter()
+                    .find(|s| {
+                        s.task
+                            .url
+                            .path_segments()
+                            .and_then(Iterator::last)
+                            .map_or(false, |i| i.eq_ignore_ascii_case(index))
+                    })
+                    .with_context(|| format!("no submissions for `{}`", latest_for))?
+                    .detail
+            }
+            // yukicoder API cannot list the submissions of the user, so use the ones recorded by
+            // `submit`.
+            TargetProblem::Yukicoder(_) => {
+                History::load(&metadata.workspace_root)?
+                    .records
+                    .into_iter()
+                    .rev()
+                    .find(|r| r.package == member.name && r.problem == *latest_for)
+                    .with_context(|| format!("no submissions for `{}` in the history", latest_for))?
+                    .submission_url
+            }
+        }
+    } else {
+        let submission = submission.as_deref().unwrap();
+
+        if let Ok(url) = submission.parse::<Url>() {
+            url
+        } else if submission.chars().all(|c| c.is_ascii_digit()) {
+            let targets = package_metadata
+                .bin
+                .iter()
+                .filter(|(index, _)| problem.as_ref().map_or(true, |p| p == *index))
+                .map(|(_, bin)| &bin.problem)
+                .collect::<Vec<_>>();
+
+            let urls = targets
+                .iter()
+                .map(|target| submission_url(target, submission))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            match &*urls {
+                [] => bail!("`package.metadata.cargo-compete.bin` is empty"),
+                [url, rest @ ..] if rest.iter().all(|u| u == url) => url.clone(),
+                _ => bail!("found multiple candicates. specify the problem with `--problem`"),
+            }
+        } else {
+            bail!("`{}` is neither a submission ID nor a URL", submission);
+        }
+    };
+
+    let code = crate::web::retrieve_submission::retrieve_code(&url, &cookies_path, shell)?;
+
+    shell.status("Retrieved", &url)?;
+
+    let output = if let Some(output) = output {
+        Some(output)
+    } else if let Some(problem) = problem {
+        let name = package_metadata
+            .bin
+            .remove(&problem)
+            .with_context(|| format!("could not find `{}`", problem))?
+            .name;
+        Some(member.bin_target(&name)?.src_path.clone())
+    } else {
+        None
+    };
+
+    if let Some(output) = output {
+        if output.exists() {
+            let reply = shell.read_reply(&format!("Overwrite `{}`? [y/N] ", output.display()))?;
+            if !matches!(&*reply.trim().to_lowercase(), "y" | "yes") {
+                shell.status("Cancelled", "writing the code")?;
+                return Ok(());
+            }
+        }
+        crate::fs::write(&output, code)?;
+        shell.status("Wrote", output.display())?;
+    } else {
+        write!(shell.out(), "{}", code)?;
+        shell.out().flush()?;
+    }
+    Ok(())
+}
+
+/// URL of the submission `id` on the platform of `target`.
+fn submission_url(target: &TargetProblem, id: &str) -> anyhow::Result<Url> {
+    let url = match target {
+        TargetProblem::Atcoder { contest, .. } => {
+            format!("https://atcoder.jp/contests/{}/submissions/{}", contest, id)
+        }
+        TargetProblem::Codeforces { contest, .. } => {
+            format!(
+                "https://codeforces.com/contest/{}/submission/{}",
+                contest, id
+            )
+        }
+        TargetProblem::Yukicoder(_) => format!("https://yukicoder.me/submissions/{}", id),
+    };
+    url.parse().map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::project::{TargetProblem, TargetProblemYukicoder};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn submission_url() -> anyhow::Result<()> {
+        let atcoder = TargetProblem::Atcoder {
+            contest: "abc999".to_owned(),
+            index: "A".to_owned(),
+            url: None,
+        };
+        let yukicoder =
+            TargetProblem::Yukicoder(TargetProblemYukicoder::Problem { no: 1, url: None });
+
+        assert_eq!(
+            "https://atcoder.jp/contests/abc999/submissions/123",
+            super::submission_url(&atcoder, "123")?.as_str(),
+        );
+        assert_eq!(
+            "https://yukicoder.me/submissions/123",
+            super::submission_url(&yukicoder, "123")?.as_str(),
+        );
+        Ok(())
+    }
+}
@@ -1,15 +1,12 @@
 use crate::{
+    history::History,
     project::{MetadataExt as _, PackageExt as _, PackageMetadataCargoCompeteBin, TargetProblem},
     shell::ColorChoice,
-    web::credentials,
+    web::submission_summaries::YukicoderSubmission,
 };
-use anyhow::bail;
+use anyhow::{bail, Context as _};
 use indexmap::indexset;
-use snowchains_core::web::{
-    Atcoder, AtcoderRetrieveSubmissionSummariesCredentials,
-    AtcoderRetrieveSubmissionSummariesTarget, CookieStorage, RetrieveSubmissionSummaries,
-};
-use std::{borrow::BorrowMut as _, cell::RefCell, path::PathBuf};
+use std::path::PathBuf;
 use structopt::StructOpt;
 use strum::VariantNames as _;
 
@@ -64,6 +61,8 @@ pub(crate) fn run(
     let package_metadata = member.read_package_metadata()?;
 
     let mut atcoder_targets = indexset!();
+    let mut codeforces_targets = indexset!();
+    let mut yukicoder_targets = vec![];
 
     for (
         bin_index,
@@ -77,49 +76,58 @@ pub(crate) fn run(
                 TargetProblem::Atcoder { contest, .. } => {
                     atcoder_targets.insert(contest.clone());
                 }
-                TargetProblem::Codeforces { .. } => {
-                    todo!("`retrieve submission-summaries` for Codeforces is not implemented");
+                TargetProblem::Codeforces { contest, .. } => {
+                    codeforces_targets.insert(contest.clone());
                 }
                 TargetProblem::Yukicoder(_) => {
-                    todo!("`retrieve submission-summaries` for yukicoder is not implemented");
+                    yukicoder_targets.push(bin_index);
                 }
             }
         }
     }
 
-    if atcoder_targets.len() > 1 {
+    if atcoder_targets.len() + codeforces_targets.len() + (!yukicoder_targets.is_empty() as usize)
+        > 1
+    {
         bail!("found multiple candicates. specify the target with argument");
     }
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-    let timeout = crate::web::TIMEOUT;
-
-    if let Some(contest) = atcoder_targets.into_iter().next() {
-        let outcome = {
-            let shell = RefCell::new(shell.borrow_mut());
-
-            let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
-                username_and_password: &mut credentials::username_and_password(
-                    &shell,
-                    "Username: ",
-                    "Password: ",
-                ),
-            };
-
-            Atcoder::exec(RetrieveSubmissionSummaries {
-                target: AtcoderRetrieveSubmissionSummariesTarget { contest },
-                credentials,
-                cookie_storage,
-                timeout,
-                shell: &shell,
-            })?
-        };
-
-        writeln!(shell.out(), "{}", outcome.to_json())?;
-        shell.out().flush()?;
+    let summaries = if let Some(contest) = atcoder_targets.into_iter().next() {
+        crate::web::submission_summaries::retrieve_atcoder(&contest, &cookies_path, shell)?
+    } else if let Some(contest) = codeforces_targets.into_iter().next() {
+        crate::web::submission_summaries::retrieve_codeforces(&contest, shell)?
+    } else if !yukicoder_targets.is_empty() {
+        // Use the submissions recorded by `submit`.
+        let history = History::load(&metadata.workspace_root)?;
+
+        let submissions = history
+            .records
+            .iter()
+            .filter(|r| r.platform == "yukicoder" && r.package == member.name)
+            .filter(|r| yukicoder_targets.contains(&&r.problem))
+            .map(|r| {
+                let task_url = package_metadata.bin[&r.problem]
+                    .problem
+                    .url()
+                    .with_context(|| format!("`url` is missing for `{}`", r.problem))?;
+                let id = crate::web::watch_submissions::parse_yukicoder_submission_id(
+                    r.submission_url.as_str(),
+                )?;
+                Ok(YukicoderSubmission {
+                    problem: &r.problem,
+                    task_url,
+                    id,
+                    submitted_at: r.submitted_at.into(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        crate::web::submission_summaries::retrieve_yukicoder(&submissions, shell)?
     } else {
         bail!("`package.metadata.cargo-compete.bin` is empty");
-    }
+    };
 
+    writeln!(shell.out(), "{}", summaries.to_json())?;
+    shell.out().flush()?;
     Ok(())
 }
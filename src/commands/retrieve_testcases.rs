@@ -16,6 +16,10 @@ pub struct OptCompeteRetrieveTestcases {
     #[structopt(long, value_name("INDEX"))]
     pub problems: Option<Vec<String>>,
 
+    /// Do not retrieve the problem pages for the memory limits and the floating-point tolerances
+    #[structopt(long)]
+    pub no_problem_pages: bool,
+
     /// Package (see `cargo help pkgid`)
     #[structopt(short, long, value_name("SPEC"))]
     pub package: Option<String>,
@@ -38,6 +42,7 @@ pub(crate) fn run(opt: OptCompeteRetrieveTestcases, ctx: crate::Context<'_>) ->
     let OptCompeteRetrieveTestcases {
         full,
         problems,
+        no_problem_pages,
         package,
         manifest_path,
         color,
@@ -86,6 +91,7 @@ pub(crate) fn run(opt: OptCompeteRetrieveTestcases, ctx: crate::Context<'_>) ->
         &mut { package_metadata_bin },
         problems,
         full,
+        !no_problem_pages,
         &metadata.workspace_root,
         &cargo_compete_config.test_suite,
         &cookies_path,
@@ -0,0 +1,120 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+};
+use anyhow::Context as _;
+use human_size::Size;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteStress {
+    /// Bin target that generates inputs. It receives the iteration number as a seed
+    #[structopt(long, value_name("NAME"), default_value("gen"))]
+    pub generator: String,
+
+    /// Bin target that is trusted to be correct
+    #[structopt(long, value_name("NAME"), default_value("naive"))]
+    pub reference: String,
+
+    /// Number of iterations. Runs until a counterexample is found if omitted
+    #[structopt(long, value_name("N"))]
+    pub iterations: Option<u64>,
+
+    /// Name of the test case to save
+    #[structopt(long, value_name("NAME"))]
+    pub name: Option<String>,
+
+    /// Shrink the counterexample before saving it
+    #[structopt(long)]
+    pub shrink: bool,
+
+    /// Display limit
+    #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
+    pub display_limit: Size,
+
+    /// Package (see `cargo help pkgid`)
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Build the artifacts in release mode, with optimizations
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Problem Index
+    pub problem: String,
+}
+
+pub(crate) fn run(opt: OptCompeteStress, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteStress {
+        generator,
+        reference,
+        iterations,
+        name,
+        shrink,
+        display_limit,
+        package,
+        release,
+        manifest_path,
+        color,
+        problem,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path: _,
+        shell,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(Ok)
+        .unwrap_or_else(|| crate::project::locate_project(cwd))?;
+    let metadata = crate::project::cargo_metadata(&manifest_path)?;
+
+    let cargo_compete_config = metadata.read_compete_toml()?;
+
+    let member = metadata.query_for_member(package.as_deref())?;
+
+    let package_metadata_bin = member
+        .read_package_metadata()?
+        .bin
+        .remove(&problem)
+        .with_context(|| {
+            format!(
+                "could not find `{}` in `package.metadata.cargo-compete.bin`",
+                problem
+            )
+        })?;
+
+    crate::testing::stress(crate::testing::StressArgs {
+        metadata: &metadata,
+        member,
+        cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+        package_metadata_bin: &package_metadata_bin,
+        default_match: cargo_compete_config.test.default_match,
+        generator: &generator,
+        reference: &reference,
+        iterations,
+        name,
+        shrink,
+        release,
+        display_limit,
+        shell,
+    })
+}
@@ -1,19 +1,16 @@
 use crate::{
+    history::{History, LocalTest, NewRecord},
     project::{
-        CargoCompeteConfigSubmitViaBinary, MetadataExt as _, PackageExt as _, TargetProblem,
+        CargoCompeteConfigLanguageId, MetadataExt as _, PackageExt as _, TargetProblem,
         TargetProblemYukicoder,
     },
     shell::ColorChoice,
+    testing::ReportFormat,
     web::credentials,
 };
 use anyhow::Context as _;
 use human_size::Size;
-use liquid::object;
-use prettytable::{
-    cell,
-    format::{FormatBuilder, LinePosition, LineSeparator},
-    row, Table,
-};
+use prettytable::{cell, row, Table};
 use snowchains_core::web::{
     Atcoder, AtcoderSubmitCredentials, AtcoderSubmitTarget, AtcoderWatchSubmissionsCredentials,
     AtcoderWatchSubmissionsTarget, Codeforces, CodeforcesSubmitCredentials, CodeforcesSubmitTarget,
@@ -34,6 +31,20 @@ pub struct OptCompeteSubmit {
     #[structopt(long)]
     pub no_test: bool,
 
+    /// Do not check the size, the crates, and the compilation with the Rust version of the platform
+    /// before submitting
+    #[structopt(long)]
+    pub no_check: bool,
+
+    /// Print the summary and the code instead of submitting it. The test is skipped, nothing is
+    /// sent to the platform, and the builds run with `--offline`
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// With `--dry-run`, write the code to the file instead of stdout
+    #[structopt(long, value_name("PATH"), requires("dry-run"))]
+    pub output: Option<PathBuf>,
+
     /// Do not watch the submission
     #[structopt(long)]
     pub no_watch: bool,
@@ -46,6 +57,19 @@ pub struct OptCompeteSubmit {
     #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
     pub display_limit: Size,
 
+    /// Format of the test report
+    #[structopt(
+        long,
+        value_name("FORMAT"),
+        possible_values(ReportFormat::VARIANTS),
+        default_value("pretty")
+    )]
+    pub format: ReportFormat,
+
+    /// Write the test report to the file instead of stdout. Ignored for `pretty`
+    #[structopt(long, value_name("PATH"))]
+    pub report_path: Option<PathBuf>,
+
     /// Existing package to retrieving test cases for
     #[structopt(short, long, value_name("SPEC"))]
     pub package: Option<String>,
@@ -74,9 +98,14 @@ pub struct OptCompeteSubmit {
 pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     let OptCompeteSubmit {
         no_test,
+        no_check,
+        dry_run,
+        output,
         no_watch,
         testcases,
         display_limit,
+        format,
+        report_path,
         package,
         release,
         manifest_path,
@@ -92,6 +121,9 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
 
     shell.set_color_choice(color);
 
+    let report_path = report_path.map(|p| cwd.join(p));
+    let output = output.map(|p| cwd.join(p));
+
     let manifest_path = manifest_path
         .map(Ok)
         .unwrap_or_else(|| crate::project::locate_project(cwd))?;
@@ -112,100 +144,99 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             )
         })?;
 
-    if !no_test {
+    // `cargo build` for the test may access the network.
+    if !(no_test || dry_run) {
         crate::testing::test(crate::testing::Args {
             metadata: &metadata,
             member,
             cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
             package_metadata_bin: &package_metadata_bin,
+            default_match: cargo_compete_config.test.default_match,
             release,
             test_case_names: testcases.map(|ss| ss.into_iter().collect()),
             display_limit,
+            memory_limit: None,
+            no_memory_limit: false,
+            as_submitted: false,
+            submit_via_binary: None,
+            sanitizer: None,
+            repeat: 1,
+            format,
+            report_path: report_path.as_deref(),
             shell,
         })?;
     }
 
     let bin = member.bin_target(&package_metadata_bin.name)?;
 
-    let code = if let Some(CargoCompeteConfigSubmitViaBinary {
-        target,
-        cross,
-        strip,
-        upx,
-    }) = &cargo_compete_config.submit_via_binary
-    {
-        let original_source_code = crate::fs::read_to_string(&bin.src_path)?;
-
-        let program = if let Some(cross) = cross {
-            cross.clone()
-        } else {
-            crate::process::cargo_exe()?
-        };
-
-        crate::process::with_which(program, &metadata.workspace_root)?
-            .args(&[
-                "build",
-                "--bin",
-                &bin.name,
-                "--release",
-                "--target",
-                &target,
-            ])
-            .cwd(member.manifest_path.parent().unwrap())
-            .display_cwd()
-            .exec_with_shell_status(shell)?;
-
-        let orig_artifact = metadata
-            .target_directory
-            .join(&target)
-            .join("release")
-            .join(&bin.name);
-
-        let artifact = tempfile::Builder::new()
-            .prefix("cargo-compete-exec-base64-encoded-binary-")
-            .tempfile()?
-            .into_temp_path();
-
-        std::fs::copy(orig_artifact, &artifact)?;
-
-        if let Some(strip) = strip {
-            crate::process::with_which(strip, &metadata.workspace_root)?
-                .arg("-s")
-                .arg(&artifact)
-                .exec_with_shell_status(shell)?;
-        }
+    let code = crate::source_code::generate(
+        &metadata,
+        member,
+        bin,
+        cargo_compete_config.submit_via_binary.as_ref(),
+        dry_run,
+        shell,
+    )?;
+
+    if !no_check {
+        crate::precheck::check(
+            &metadata,
+            member,
+            &bin.name,
+            &code,
+            &package_metadata_bin.problem,
+            cargo_compete_config.submit_via_binary.is_some(),
+            dry_run,
+            shell,
+        )?;
+    }
+
+    let source_code_len = code.len();
+
+    let language_id = package_metadata_bin.language_id.clone().unwrap_or_else(|| {
+        let CargoCompeteConfigLanguageId {
+            atcoder,
+            codeforces,
+            yukicoder,
+        } = &cargo_compete_config.language_id;
 
-        if let Some(upx) = upx {
-            crate::process::with_which(upx, &metadata.workspace_root)?
-                .arg("--best")
-                .arg(&artifact)
-                .exec_with_shell_status(shell)?;
+        match package_metadata_bin.problem {
+            TargetProblem::Atcoder { .. } => atcoder.as_deref().unwrap_or(ATCODER_RUST_LANG_ID),
+            TargetProblem::Codeforces { .. } => {
+                codeforces.as_deref().unwrap_or(CODEFORCES_RUST_LANG_ID)
+            }
+            TargetProblem::Yukicoder(_) => yukicoder.as_deref().unwrap_or(YUKICODER_RUST_LANG_ID),
         }
+        .to_owned()
+    });
 
-        let artifact_binary = crate::fs::read(&artifact)?;
-
-        artifact.close()?;
-
-        liquid::ParserBuilder::with_stdlib()
-            .build()?
-            .parse(include_str!(
-                "../../resources/exec-base64-encoded-binary.rs.liquid"
-            ))?
-            .render(&object!({
-                "source_code": original_source_code,
-                "base64": base64::encode(artifact_binary),
-            }))?
-    } else {
-        crate::fs::read_to_string(&bin.src_path)?
-    };
+    if dry_run || cargo_compete_config.confirm_before_submit {
+        let mut table = summary_table(&package_metadata_bin.problem);
+        table.add_row(row!["Language ID", &language_id]);
+        table.add_row(row!["Size", source_code_len]);
 
-    let source_code_len = code.len();
+        write!(shell.err(), "{}", table)?;
+        shell.err().flush()?;
+    }
 
-    let language_id = match package_metadata_bin.problem {
-        TargetProblem::Atcoder { .. } => ATCODER_RUST_LANG_ID,
-        TargetProblem::Codeforces { .. } => CODEFORCES_RUST_LANG_ID,
-        TargetProblem::Yukicoder(_) => YUKICODER_RUST_LANG_ID,
-    };
+    if dry_run {
+        if let Some(output) = output {
+            crate::fs::write(&output, code)?;
+            shell.status("Wrote", output.display())?;
+        } else {
+            write!(shell.out(), "{}", code)?;
+            shell.out().flush()?;
+        }
+        return Ok(());
+    }
+
+    if cargo_compete_config.confirm_before_submit {
+        let reply = shell.read_reply("Submit? [y/N] ")?;
+        if !matches!(&*reply.trim().to_lowercase(), "y" | "yes") {
+            shell.status("Cancelled", "the submission")?;
+            return Ok(());
+        }
+    }
 
     let cookie_storage = CookieStorage::with_jsonl(&cookies_path)?;
     let timeout = crate::web::TIMEOUT;
@@ -228,8 +259,8 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     problem: index.clone(),
                 },
                 credentials,
-                language_id: ATCODER_RUST_LANG_ID.to_owned(),
-                code,
+                language_id: language_id.clone(),
+                code: code.clone(),
                 watch_submission: false,
                 cookie_storage,
                 timeout,
@@ -257,8 +288,8 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     problem: index.clone(),
                 },
                 credentials,
-                language_id: CODEFORCES_RUST_LANG_ID.to_owned(),
-                code,
+                language_id: language_id.clone(),
+                code: code.clone(),
                 watch_submission: false,
                 cookie_storage,
                 timeout,
@@ -280,8 +311,8 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     }
                 },
                 credentials,
-                language_id: YUKICODER_RUST_LANG_ID.to_owned(),
-                code,
+                language_id: language_id.clone(),
+                code: code.clone(),
                 watch_submission: false,
                 cookie_storage: (),
                 timeout,
@@ -292,19 +323,35 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
 
     shell.status("Successfully", "submitted the code")?;
 
-    let mut table = Table::new();
-
-    *table.get_format() = FormatBuilder::new()
-        .padding(1, 1)
-        .column_separator('│')
-        .borders('│')
-        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
-        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
-        .separator(LinePosition::Intern, LineSeparator::new('─', '┼', '├', '┤'))
-        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
-        .build();
+    let mut history = History::load(&metadata.workspace_root)?;
+    let history_id = history.push(NewRecord {
+        platform: match &package_metadata_bin.problem {
+            TargetProblem::Atcoder { .. } => "atcoder",
+            TargetProblem::Codeforces { .. } => "codeforces",
+            TargetProblem::Yukicoder(_) => "yukicoder",
+        },
+        contest: match &package_metadata_bin.problem {
+            TargetProblem::Atcoder { contest, .. }
+            | TargetProblem::Codeforces { contest, .. }
+            | TargetProblem::Yukicoder(TargetProblemYukicoder::Contest { contest, .. }) => {
+                Some(contest)
+            }
+            TargetProblem::Yukicoder(TargetProblemYukicoder::Problem { .. }) => None,
+        },
+        package: &member.name,
+        problem: &problem,
+        submission_url: outcome.submission_url.clone(),
+        language_id: &language_id,
+        code: &code,
+        local_test: if no_test {
+            LocalTest::Skipped
+        } else {
+            LocalTest::Passed
+        },
+    })?;
 
-    table.add_row(row!["Language ID", language_id]);
+    let mut table = summary_table(&package_metadata_bin.problem);
+    table.add_row(row!["Language ID", &language_id]);
     table.add_row(row!["Size", source_code_len]);
     table.add_row(row!["URL (submissions)", outcome.submissions_url]);
     table.add_row(row!["URL (detail)", outcome.submission_url]);
@@ -313,37 +360,89 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
     shell.err().flush()?;
 
     if !no_watch {
-        let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+        let cookie_storage = CookieStorage::with_jsonl(&cookies_path)?;
         let timeout = crate::web::TIMEOUT;
 
         match package_metadata_bin.problem {
             TargetProblem::Atcoder { contest, .. } => {
-                let shell = RefCell::new(shell);
-
-                let credentials = AtcoderWatchSubmissionsCredentials {
-                    username_and_password: &mut credentials::username_and_password(
-                        &shell,
-                        "Username: ",
-                        "Password: ",
-                    ),
-                };
-
-                Atcoder::exec(WatchSubmissions {
-                    target: AtcoderWatchSubmissionsTarget { contest },
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
-                })?;
+                {
+                    let shell = RefCell::new(shell.borrow_mut());
+
+                    let credentials = AtcoderWatchSubmissionsCredentials {
+                        username_and_password: &mut credentials::username_and_password(
+                            &shell,
+                            "Username: ",
+                            "Password: ",
+                        ),
+                    };
+
+                    Atcoder::exec(WatchSubmissions {
+                        target: AtcoderWatchSubmissionsTarget {
+                            contest: contest.clone(),
+                        },
+                        credentials,
+                        cookie_storage,
+                        timeout,
+                        shell: &shell,
+                    })?;
+                }
+
+                // `WatchSubmissions` returns nothing, so look for the final verdict afterwards.
+                if let Some(summary) = crate::web::submission_summaries::retrieve_atcoder(
+                    &contest,
+                    &cookies_path,
+                    shell,
+                )?
+                .summaries
+                .into_iter()
+                .find(|s| s.detail == outcome.submission_url)
+                {
+                    history.set_verdict(history_id, summary.status)?;
+                }
             }
-            TargetProblem::Codeforces { .. } => {
-                shell.warn("watching submissions for Codeforces is not implemented")?;
+            TargetProblem::Codeforces { contest, .. } => {
+                let verdict = crate::web::watch_submissions::watch_codeforces(
+                    &contest,
+                    Some(&outcome.submission_url),
+                    shell,
+                )?;
+                history.set_verdict(history_id, verdict)?;
             }
             TargetProblem::Yukicoder(_) => {
-                shell.warn("watching submissions for yukicoder is not implemented")?;
+                let verdict = crate::web::watch_submissions::watch_yukicoder(
+                    outcome.submission_url.as_str(),
+                    shell,
+                )?;
+                history.set_verdict(history_id, verdict)?;
             }
         }
     }
 
     Ok(())
 }
+
+/// Table with the contest, the problem, and the URL of `problem`.
+fn summary_table(problem: &TargetProblem) -> Table {
+    let mut table = crate::shell::table();
+
+    let (contest, index) = match problem {
+        TargetProblem::Atcoder { contest, index, .. }
+        | TargetProblem::Codeforces { contest, index, .. }
+        | TargetProblem::Yukicoder(TargetProblemYukicoder::Contest { contest, index, .. }) => {
+            (&**contest, index.clone())
+        }
+        TargetProblem::Yukicoder(TargetProblemYukicoder::Problem { no, .. }) => {
+            ("-", no.to_string())
+        }
+    };
+
+    table.add_row(row!["Contest", contest]);
+    table.add_row(row!["Problem", index]);
+    table.add_row(row![
+        "URL (problem)",
+        problem
+            .url()
+            .map_or_else(|| "-".to_owned(), ToString::to_string),
+    ]);
+    table
+}
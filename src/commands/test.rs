@@ -1,10 +1,11 @@
 use crate::{
     project::{MetadataExt as _, PackageExt as _},
     shell::ColorChoice,
+    testing::{ReportFormat, Sanitizer},
 };
 use anyhow::Context as _;
 use human_size::Size;
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 
@@ -18,6 +19,27 @@ pub struct OptCompeteTest {
     #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
     pub display_limit: Size,
 
+    /// Memory limit. Overrides the one in the test suite
+    #[structopt(long, value_name("SIZE"))]
+    pub memory_limit: Option<Size>,
+
+    /// Do not apply the memory limit
+    #[structopt(long, conflicts_with("memory-limit"))]
+    pub no_memory_limit: bool,
+
+    /// Format of the test report
+    #[structopt(
+        long,
+        value_name("FORMAT"),
+        possible_values(ReportFormat::VARIANTS),
+        default_value("pretty")
+    )]
+    pub format: ReportFormat,
+
+    /// Write the test report to the file instead of stdout. Ignored for `pretty`
+    #[structopt(long, value_name("PATH"))]
+    pub report_path: Option<PathBuf>,
+
     /// Existing package to retrieving test cases for
     #[structopt(short, long, value_name("SPEC"))]
     pub package: Option<String>,
@@ -26,6 +48,51 @@ pub struct OptCompeteTest {
     #[structopt(long)]
     pub release: bool,
 
+    /// Test the code that `submit` would send, compiled as a standalone package in release mode
+    #[structopt(long, conflicts_with("bless"))]
+    pub as_submitted: bool,
+
+    /// Build and run the solution with a sanitizer or Miri, on a nightly toolchain
+    #[structopt(
+        long,
+        value_name("SANITIZER"),
+        possible_values(Sanitizer::VARIANTS),
+        conflicts_with_all(&["as-submitted", "bless"])
+    )]
+    pub sanitize: Option<Sanitizer>,
+
+    /// Run each test case N times, and report the pass rates, the time variance, and the cases
+    /// whose outputs differ between the runs
+    #[structopt(long, value_name("N"))]
+    pub repeat: Option<NonZeroUsize>,
+
+    /// Re-run the test every time the source code or the test suite is modified
+    #[structopt(long)]
+    pub watch: bool,
+
+    /// Record the outputs of `--with` as the expected outputs of the cases that have none or are
+    /// `pending`, instead of testing
+    #[structopt(long, requires("with"), conflicts_with_all(&["all", "watch"]))]
+    pub bless: bool,
+
+    /// Bin target that is trusted to be correct, for `--bless`
+    #[structopt(long, value_name("NAME"), requires("bless"))]
+    pub with: Option<String>,
+
+    /// Test all of the problems in the package
+    #[structopt(
+        long,
+        conflicts_with_all(&[
+            "problem",
+            "testcases",
+            "watch",
+            "as-submitted",
+            "sanitize",
+            "repeat",
+        ])
+    )]
+    pub all: bool,
+
     /// Path to Cargo.toml
     #[structopt(long, value_name("PATH"))]
     pub manifest_path: Option<PathBuf>,
@@ -40,15 +107,27 @@ pub struct OptCompeteTest {
     pub color: ColorChoice,
 
     /// Problem Index
-    pub problem: String,
+    #[structopt(required_unless("all"))]
+    pub problem: Option<String>,
 }
 
 pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     let OptCompeteTest {
         testcases,
         display_limit,
+        memory_limit,
+        no_memory_limit,
+        format,
+        report_path,
         package,
         release,
+        as_submitted,
+        sanitize,
+        repeat,
+        watch,
+        bless,
+        with,
+        all,
         manifest_path,
         color,
         problem,
@@ -62,6 +141,8 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
 
     shell.set_color_choice(color);
 
+    let report_path = report_path.map(|p| cwd.join(p));
+
     let manifest_path = manifest_path
         .map(Ok)
         .unwrap_or_else(|| crate::project::locate_project(cwd))?;
@@ -71,25 +152,72 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
 
     let member = metadata.query_for_member(package.as_deref())?;
 
-    let package_metadata_bin = member
-        .read_package_metadata()?
-        .bin
-        .remove(&problem)
-        .with_context(|| {
-            format!(
-                "could not find `{}` in `package.metadata.cargo-compete.bin`",
-                problem
-            )
-        })?;
-
-    crate::testing::test(crate::testing::Args {
+    let mut package_metadata_bins = member.read_package_metadata()?.bin;
+
+    if all {
+        let package_metadata_bins = package_metadata_bins.values().collect();
+
+        return crate::testing::test_problems(crate::testing::ProblemsArgs {
+            metadata: &metadata,
+            member,
+            cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+            package_metadata_bins,
+            default_match: cargo_compete_config.test.default_match,
+            release,
+            display_limit,
+            memory_limit,
+            no_memory_limit,
+            format,
+            report_path: report_path.as_deref(),
+            shell,
+        });
+    }
+
+    let problem = problem.unwrap();
+
+    let package_metadata_bin = package_metadata_bins.remove(&problem).with_context(|| {
+        format!(
+            "could not find `{}` in `package.metadata.cargo-compete.bin`",
+            problem
+        )
+    })?;
+
+    if bless {
+        return crate::testing::bless(crate::testing::BlessArgs {
+            metadata: &metadata,
+            member,
+            cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+            package_metadata_bin: &package_metadata_bin,
+            with: with.as_deref().unwrap(),
+            default_match: cargo_compete_config.test.default_match,
+            release,
+            shell,
+        });
+    }
+
+    let args = crate::testing::Args {
         metadata: &metadata,
         member,
         cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
         package_metadata_bin: &package_metadata_bin,
+        default_match: cargo_compete_config.test.default_match,
         release,
         test_case_names: testcases.map(|ss| ss.into_iter().collect()),
         display_limit,
+        memory_limit,
+        no_memory_limit,
+        as_submitted,
+        submit_via_binary: cargo_compete_config.submit_via_binary.as_ref(),
+        sanitizer: sanitize,
+        repeat: repeat.map_or(1, NonZeroUsize::get),
+        format,
+        report_path: report_path.as_deref(),
         shell,
-    })
+    };
+
+    if watch {
+        crate::testing::watch(args)
+    } else {
+        crate::testing::test(args)
+    }
 }
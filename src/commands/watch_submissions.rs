@@ -19,10 +19,10 @@ pub struct OptCompeteWatchSubmissions {
     pub color: ColorChoice,
 
     /// Platform
-    #[structopt(possible_value("atcoder"))]
+    #[structopt(possible_values(PlatformKind::KEBAB_CASE_VARIANTS))]
     pub platform: PlatformKind,
 
-    /// Contest ID
+    /// Contest ID. For yukicoder, submission ID or URL
     pub contest: String,
 }
 
@@ -41,28 +41,35 @@ pub(crate) fn run(opt: OptCompeteWatchSubmissions, ctx: crate::Context<'_>) -> a
 
     shell.set_color_choice(color);
 
-    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
-    let timeout = crate::web::TIMEOUT;
+    match platform {
+        PlatformKind::Atcoder => {
+            let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+            let timeout = crate::web::TIMEOUT;
 
-    if platform == PlatformKind::Atcoder {
-        let shell = RefCell::new(shell);
+            let shell = RefCell::new(shell);
 
-        let credentials = AtcoderWatchSubmissionsCredentials {
-            username_and_password: &mut credentials::username_and_password(
-                &shell,
-                "Username: ",
-                "Password: ",
-            ),
-        };
+            let credentials = AtcoderWatchSubmissionsCredentials {
+                username_and_password: &mut credentials::username_and_password(
+                    &shell,
+                    "Username: ",
+                    "Password: ",
+                ),
+            };
 
-        Atcoder::exec(WatchSubmissions {
-            target: AtcoderWatchSubmissionsTarget { contest },
-            credentials,
-            cookie_storage,
-            timeout,
-            shell: &shell,
-        })
-    } else {
-        unreachable!()
+            Atcoder::exec(WatchSubmissions {
+                target: AtcoderWatchSubmissionsTarget { contest },
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        // The verdicts are printed by the functions.
+        PlatformKind::Codeforces => {
+            crate::web::watch_submissions::watch_codeforces(&contest, None, shell).map(drop)
+        }
+        PlatformKind::Yukicoder => {
+            crate::web::watch_submissions::watch_yukicoder(&contest, shell).map(drop)
+        }
     }
 }
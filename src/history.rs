@@ -0,0 +1,245 @@
+//! Ledger of the submissions made by `submit`, saved under the workspace.
+
+use anyhow::{bail, Context as _};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+use url::Url;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Record {
+    pub(crate) id: u64,
+    pub(crate) submitted_at: DateTime<Local>,
+    pub(crate) platform: String,
+    pub(crate) contest: Option<String>,
+    pub(crate) package: String,
+    /// Index for `package.metadata.cargo-compete.bin`.
+    pub(crate) problem: String,
+    pub(crate) submission_url: Url,
+    pub(crate) language_id: String,
+    pub(crate) source_sha256: String,
+    pub(crate) git_commit: Option<String>,
+    /// Whether the working tree had uncommitted changes.
+    pub(crate) git_dirty: bool,
+    pub(crate) local_test: LocalTest,
+    /// `None` until the submission is watched.
+    pub(crate) verdict: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum LocalTest {
+    Passed,
+    Skipped,
+}
+
+/// Fields of a [`Record`] that `submit` knows.
+pub(crate) struct NewRecord<'a> {
+    pub(crate) platform: &'a str,
+    pub(crate) contest: Option<&'a str>,
+    pub(crate) package: &'a str,
+    pub(crate) problem: &'a str,
+    pub(crate) submission_url: Url,
+    pub(crate) language_id: &'a str,
+    pub(crate) code: &'a str,
+    pub(crate) local_test: LocalTest,
+}
+
+/// `.cargo-compete/history.json` and `.cargo-compete/sources/{id}.rs` under the workspace root.
+pub(crate) struct History {
+    dir: PathBuf,
+    pub(crate) records: Vec<Record>,
+}
+
+impl History {
+    pub(crate) fn load(workspace_root: &Path) -> anyhow::Result<Self> {
+        let dir = workspace_root.join(".cargo-compete");
+        let path = dir.join("history.json");
+        let records = if path.exists() {
+            crate::fs::read_json(path)?
+        } else {
+            vec![]
+        };
+        Ok(Self { dir, records })
+    }
+
+    /// Appends a record, saves the code, and returns the ID.
+    pub(crate) fn push(&mut self, record: NewRecord<'_>) -> anyhow::Result<u64> {
+        let NewRecord {
+            platform,
+            contest,
+            package,
+            problem,
+            submission_url,
+            language_id,
+            code,
+            local_test,
+        } = record;
+
+        let id = self.records.last().map_or(1, |r| r.id + 1);
+        let (git_commit, git_dirty) = git_status(self.dir.parent().unwrap());
+
+        crate::fs::create_dir_all(self.dir.join("sources"))?;
+        crate::fs::write(self.source_path(id), code)?;
+
+        self.records.push(Record {
+            id,
+            submitted_at: Local::now(),
+            platform: platform.to_owned(),
+            contest: contest.map(ToOwned::to_owned),
+            package: package.to_owned(),
+            problem: problem.to_owned(),
+            submission_url,
+            language_id: language_id.to_owned(),
+            source_sha256: hex::encode(Sha256::digest(code.as_bytes())),
+            git_commit,
+            git_dirty,
+            local_test,
+            verdict: None,
+        });
+        self.save()?;
+        Ok(id)
+    }
+
+    pub(crate) fn set_verdict(&mut self, id: u64, verdict: String) -> anyhow::Result<()> {
+        if let Some(record) = self.records.iter_mut().find(|r| r.id == id) {
+            record.verdict = Some(verdict);
+        }
+        self.save()
+    }
+
+    /// Reads the code sent in the submission, and checks it against the hash.
+    pub(crate) fn source(&self, id: u64) -> anyhow::Result<String> {
+        let record = self
+            .records
+            .iter()
+            .find(|r| r.id == id)
+            .with_context(|| format!("no such record: {}", id))?;
+
+        let code = crate::fs::read_to_string(self.source_path(id))?;
+        if hex::encode(Sha256::digest(code.as_bytes())) != record.source_sha256 {
+            bail!("`{}` has been modified", self.source_path(id).display());
+        }
+        Ok(code)
+    }
+
+    fn source_path(&self, id: u64) -> PathBuf {
+        self.dir.join("sources").join(format!("{}.rs", id))
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        crate::fs::create_dir_all(&self.dir)?;
+        crate::fs::write(
+            self.dir.join("history.json"),
+            serde_json::to_string_pretty(&self.records)?,
+        )
+    }
+}
+
+/// `HEAD` and whether the working tree is dirty. `(None, false)` if it is not a Git repository.
+fn git_status(workspace_root: &Path) -> (Option<String>, bool) {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(workspace_root)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())
+    };
+
+    match git(&["rev-parse", "HEAD"]) {
+        Some(commit) => {
+            let dirty = git(&[
+                "status",
+                "--porcelain",
+                "--",
+                ".",
+                ":(exclude).cargo-compete",
+            ])
+            .map_or(false, |s| !s.is_empty());
+            (Some(commit), dirty)
+        }
+        None => (None, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, LocalTest, NewRecord};
+    use pretty_assertions::assert_eq;
+
+    fn new_record(code: &str) -> NewRecord<'_> {
+        NewRecord {
+            platform: "atcoder",
+            contest: Some("practice"),
+            package: "practice",
+            problem: "a",
+            submission_url: "https://atcoder.jp/contests/practice/submissions/1"
+                .parse()
+                .unwrap(),
+            language_id: "4050",
+            code,
+            local_test: LocalTest::Passed,
+        }
+    }
+
+    #[test]
+    fn push() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let mut history = History::load(tempdir.path())?;
+        assert_eq!(1, history.push(new_record("fn main() {}\n"))?);
+        assert_eq!(2, history.push(new_record("fn main() {}\n"))?);
+
+        let history = History::load(tempdir.path())?;
+        assert_eq!(
+            vec![1, 2],
+            history.records.iter().map(|r| r.id).collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            "536e506bb90914c243a12b397b9a998f85ae2cbd9ba02dfd03a9e155ca5ca0f4",
+            history.records[0].source_sha256,
+        );
+        assert_eq!(None, history.records[0].verdict);
+
+        tempdir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn source() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let mut history = History::load(tempdir.path())?;
+        let id = history.push(new_record("fn main() {}\n"))?;
+        assert_eq!("fn main() {}\n", history.source(id)?);
+
+        std::fs::write(history.source_path(id), "fn main() { todo!() }\n")?;
+        assert!(history.source(id).is_err());
+        assert!(history.source(id + 1).is_err());
+
+        tempdir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn set_verdict() -> anyhow::Result<()> {
+        let tempdir = tempfile::tempdir()?;
+
+        let mut history = History::load(tempdir.path())?;
+        let id = history.push(new_record("fn main() {}\n"))?;
+        history.set_verdict(id, "AC".to_owned())?;
+
+        let history = History::load(tempdir.path())?;
+        assert_eq!(Some("AC"), history.records[0].verdict.as_deref());
+
+        tempdir.close()?;
+        Ok(())
+    }
+}
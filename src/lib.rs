@@ -1,23 +1,32 @@
-#![forbid(unsafe_code)]
+// Not `forbid`. `testing` calls `wait4(2)`, `setrlimit(2)` and `prctl(2)` through `libc` in a few
+// items marked with `#[allow(unsafe_code)]`.
+#![deny(unsafe_code)]
 #![warn(rust_2018_idioms)]
 
+mod bundle;
 mod commands;
 mod fs;
+mod history;
 mod open;
+mod precheck;
 mod process;
 mod project;
 pub mod shell;
+mod source_code;
 mod testing;
 mod web;
 
 use crate::{
     commands::{
-        init::OptCompeteInit, login::OptCompeteLogin,
-        migrate_cargo_atcoder::OptCompeteMigrateCargoAtcoder, new::OptCompeteNew,
-        open::OptCompeteOpen, participate::OptCompeteParticipate,
+        add_testcase::OptCompeteAddTestcase, bench::OptCompeteBench, bundle::OptCompeteBundle,
+        history::OptCompeteHistory, init::OptCompeteInit, languages::OptCompeteLanguages,
+        login::OptCompeteLogin, migrate_cargo_atcoder::OptCompeteMigrateCargoAtcoder,
+        new::OptCompeteNew, open::OptCompeteOpen, participate::OptCompeteParticipate,
+        retrieve_submission::OptCompeteRetrieveSubmission,
         retrieve_submission_summaries::OptCompeteRetrieveSubmissionSummaries,
-        retrieve_testcases::OptCompeteRetrieveTestcases, submit::OptCompeteSubmit,
-        test::OptCompeteTest, watch_submissions::OptCompeteWatchSubmissions,
+        retrieve_testcases::OptCompeteRetrieveTestcases, shrink::OptCompeteShrink,
+        stress::OptCompeteStress, submit::OptCompeteSubmit, test::OptCompeteTest,
+        watch_submissions::OptCompeteWatchSubmissions,
     },
     shell::Shell,
 };
@@ -96,6 +105,34 @@ pub enum OptCompete {
     /// Submit your code
     #[structopt(author, visible_alias("s"))]
     Submit(OptCompeteSubmit),
+
+    /// Search for a counterexample with a generator and a reference solution
+    #[structopt(author)]
+    Stress(OptCompeteStress),
+
+    /// Minimize a failing input
+    #[structopt(author)]
+    Shrink(OptCompeteShrink),
+
+    /// Add a test case to the test suite
+    #[structopt(author)]
+    AddTestcase(OptCompeteAddTestcase),
+
+    /// Compare the speed of the solution with another bin or a Git revision
+    #[structopt(author)]
+    Bench(OptCompeteBench),
+
+    /// Expand local library crates into the source code and print it
+    #[structopt(author)]
+    Bundle(OptCompeteBundle),
+
+    /// List the languages in the submit form of a platform
+    #[structopt(author)]
+    Languages(OptCompeteLanguages),
+
+    /// List the submissions made by `submit`
+    #[structopt(author)]
+    History(OptCompeteHistory),
 }
 
 #[derive(StructOpt, Debug)]
@@ -114,6 +151,10 @@ pub enum OptCompeteRetrieve {
     /// Retrieve submission summaries
     #[structopt(author, visible_alias("ss"))]
     SubmissionSummaries(OptCompeteRetrieveSubmissionSummaries),
+
+    /// Retrieve the source code of a submission
+    #[structopt(author, visible_alias("s"))]
+    Submission(OptCompeteRetrieveSubmission),
 }
 
 #[derive(StructOpt, Debug)]
@@ -129,6 +170,12 @@ pub struct Context<'s> {
     pub shell: &'s mut Shell,
 }
 
+/// Exits after running a program under test, if this process is executed as the shim that
+/// measures its resource usage.
+pub fn exit_if_measure_shim() {
+    testing::exit_if_shim();
+}
+
 pub fn run(opt: OptCompete, ctx: Context<'_>) -> anyhow::Result<()> {
     match opt {
         OptCompete::Init(opt) => commands::init::run(opt, ctx),
@@ -144,11 +191,21 @@ pub fn run(opt: OptCompete, ctx: Context<'_>) -> anyhow::Result<()> {
         OptCompete::Retrieve(OptCompeteRetrieve::SubmissionSummaries(opt)) => {
             commands::retrieve_submission_summaries::run(opt, ctx)
         }
+        OptCompete::Retrieve(OptCompeteRetrieve::Submission(opt)) => {
+            commands::retrieve_submission::run(opt, ctx)
+        }
         OptCompete::Watch(OptCompeteWatch::Submissions(opt)) => {
             commands::watch_submissions::run(opt, ctx)
         }
         OptCompete::Open(opt) => commands::open::run(opt, ctx),
         OptCompete::Test(opt) => commands::test::run(opt, ctx),
         OptCompete::Submit(opt) => commands::submit::run(opt, ctx),
+        OptCompete::Stress(opt) => commands::stress::run(opt, ctx),
+        OptCompete::Shrink(opt) => commands::shrink::run(opt, ctx),
+        OptCompete::AddTestcase(opt) => commands::add_testcase::run(opt, ctx),
+        OptCompete::Bench(opt) => commands::bench::run(opt, ctx),
+        OptCompete::Bundle(opt) => commands::bundle::run(opt, ctx),
+        OptCompete::Languages(opt) => commands::languages::run(opt, ctx),
+        OptCompete::History(opt) => commands::history::run(opt, ctx),
     }
 }
@@ -9,6 +9,8 @@ use structopt::StructOpt as _;
 use termcolor::{Color, ColorSpec, WriteColor};
 
 fn main() {
+    cargo_compete::exit_if_measure_shim();
+
     let Opt::Compete(opt) = Opt::from_args();
     let mut shell = Shell::new();
 
@@ -0,0 +1,195 @@
+//! Checks on the code that `submit` is about to send, so that obvious rejections by the platforms
+//! are caught before submitting.
+
+use crate::{
+    project::TargetProblem, shell::Shell, ATCODER_RUST_VERSION, CODEFORCES_RUST_VERSION,
+    YUKICODER_RUST_VERSION,
+};
+use anyhow::{bail, ensure, Context as _};
+use cargo_metadata::{Metadata, Package};
+use maplit::btreemap;
+use semver::Version;
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+};
+
+/// Checks that `code` would be accepted by the platform of `problem`.
+///
+/// 1. The size of `code` is within the limit of the platform.
+/// 2. The crates used in `code` are provided by the platform, in the same versions.
+/// 3. `code` compiles with the Rust version of the platform.
+///
+/// If `via_binary` is `true`, `code` is regarded as one generated for `submit-via-binary` and the
+/// second check is skipped. The third check is skipped with a warning if the toolchain is not
+/// installed, and is performed with `--offline` if `offline` is `true`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check(
+    metadata: &Metadata,
+    member: &Package,
+    bin_name: &str,
+    code: &str,
+    problem: &TargetProblem,
+    via_binary: bool,
+    offline: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let (platform, size_limit, toolchain) = match problem {
+        TargetProblem::Atcoder { .. } => ("AtCoder", 512 * 1024, &ATCODER_RUST_VERSION),
+        TargetProblem::Codeforces { .. } => ("Codeforces", 64 * 1024, &CODEFORCES_RUST_VERSION),
+        TargetProblem::Yukicoder(_) => ("yukicoder", 64 * 1024, &YUKICODER_RUST_VERSION),
+    };
+
+    ensure!(
+        code.len() <= size_limit,
+        "the code is {} bytes, which exceeds the limit of {} ({} bytes){}",
+        code.len(),
+        platform,
+        size_limit,
+        if via_binary {
+            ". enable `strip` or `upx` in `submit-via-binary`, or disable `submit-via-binary`"
+        } else {
+            ""
+        },
+    );
+
+    if !via_binary {
+        let allowed = allowed_crates(problem)?;
+
+        for (name_in_code, package) in used_crates(metadata, member, code)? {
+            match allowed.get(&*package.name) {
+                None => bail!(
+                    "`{}` (`{}`) is not available on {}",
+                    name_in_code,
+                    package.name,
+                    platform,
+                ),
+                Some(version) if *version != package.version => bail!(
+                    "`{}` is resolved to v{}, but {} provides v{}",
+                    package.name,
+                    package.version,
+                    platform,
+                    version,
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+
+    if !is_installed(toolchain, &metadata.workspace_root, shell)? {
+        shell.warn(format!(
+            "Rust {} is not installed. skipping the compilation check. run `rustup toolchain \
+             install {}` to enable it",
+            toolchain, toolchain,
+        ))?;
+        return Ok(());
+    }
+
+    shell.status("Checking", format!("the code with Rust {}", toolchain))?;
+    crate::source_code::check(
+        metadata,
+        member,
+        bin_name,
+        code,
+        !via_binary,
+        Some(toolchain),
+        offline,
+        shell,
+    )
+}
+
+/// Whether rustup has the `toolchain`. Regarded as not installed if rustup is not found.
+fn is_installed(toolchain: &Version, cwd: &Path, shell: &mut Shell) -> anyhow::Result<bool> {
+    let rustup = match crate::process::with_which("rustup", cwd) {
+        Ok(rustup) => rustup,
+        Err(_) => return Ok(false),
+    };
+
+    let toolchains = rustup
+        .args(&["toolchain", "list"])
+        .read_with_shell_status(shell)?;
+
+    let toolchain = toolchain.to_string();
+    Ok(toolchains.lines().any(|line| {
+        line.split_whitespace().next().map_or(false, |name| {
+            name == toolchain || name.starts_with(&format!("{}-", toolchain))
+        })
+    }))
+}
+
+/// Crates that the platform provides, and their versions.
+fn allowed_crates(problem: &TargetProblem) -> anyhow::Result<BTreeMap<String, Version>> {
+    let deps = match problem {
+        TargetProblem::Atcoder { .. } => include_str!("../resources/atcoder-deps.toml"),
+        // Only the standard library is available.
+        TargetProblem::Codeforces { .. } | TargetProblem::Yukicoder(_) => return Ok(btreemap!()),
+    };
+
+    toml::from_str::<toml::value::Table>(deps)?
+        .into_iter()
+        .map(|(name, value)| {
+            let version = value
+                .as_str()
+                .or_else(|| value.get("version")?.as_str())
+                .with_context(|| format!("missing version for `{}`", name))?;
+            let version = version.trim_start_matches('=').parse()?;
+            Ok((name, version))
+        })
+        .collect()
+}
+
+/// External dependencies of `member` and the bundled local libraries referred in `code`, with the
+/// names in the code.
+fn used_crates<'a>(
+    metadata: &'a Metadata,
+    member: &'a Package,
+    code: &str,
+) -> anyhow::Result<Vec<(&'a str, &'a Package)>> {
+    let idents = crate::bundle::referenced_idents(code)?;
+
+    let nodes = &metadata
+        .resolve
+        .as_ref()
+        .with_context(|| "missing the dependency graph")?
+        .nodes;
+
+    let mut used = vec![];
+    let mut visited = HashSet::new();
+    for package in crate::source_code::bundled_packages(metadata, member)? {
+        let node = nodes
+            .iter()
+            .find(|n| n.id == package.id)
+            .with_context(|| format!("could not find `{}` in the dependency graph", package.id))?;
+
+        for dep in &node.deps {
+            let dep_package = &metadata[&dep.pkg];
+            // Local libraries are bundled into the code.
+            if dep_package.source.is_some()
+                && idents.contains(&*dep.name)
+                && visited.insert((&*dep.name, &dep.pkg))
+            {
+                used.push((&*dep.name, dep_package));
+            }
+        }
+    }
+    Ok(used)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::project::TargetProblem;
+    use pretty_assertions::assert_eq;
+    use semver::Version;
+
+    #[test]
+    fn allowed_crates() -> anyhow::Result<()> {
+        let allowed = super::allowed_crates(&TargetProblem::Atcoder {
+            contest: "practice".to_owned(),
+            index: "A".to_owned(),
+            url: None,
+        })?;
+        assert_eq!(Some(&"0.7.3".parse::<Version>()?), allowed.get("rand"));
+        assert_eq!(Some(&"0.3.6".parse::<Version>()?), allowed.get("proconio"));
+        Ok(())
+    }
+}
@@ -14,6 +14,7 @@ use std::{
 pub(crate) struct ProcessBuilder<C: Presence<PathBuf>> {
     program: OsString,
     args: Vec<OsString>,
+    envs: Vec<(OsString, OsString)>,
     cwd: C::Value,
     display_cwd: bool,
     pipe_input: Option<Vec<u8>>,
@@ -30,10 +31,17 @@ impl<C: Presence<PathBuf>> ProcessBuilder<C> {
         self
     }
 
+    pub(crate) fn env(mut self, key: impl AsRef<OsStr>, val: impl AsRef<OsStr>) -> Self {
+        self.envs
+            .push((key.as_ref().to_owned(), val.as_ref().to_owned()));
+        self
+    }
+
     pub(crate) fn cwd(self, cwd: impl AsRef<Path>) -> ProcessBuilder<Present> {
         ProcessBuilder {
             program: self.program,
             args: self.args,
+            envs: self.envs,
             cwd: cwd.as_ref().to_owned(),
             display_cwd: self.display_cwd,
             pipe_input: self.pipe_input,
@@ -51,6 +59,24 @@ impl<C: Presence<PathBuf>> ProcessBuilder<C> {
         self.pipe_input = pipe_input.map(Into::into);
         self
     }
+
+    pub(crate) fn to_command_line(&self) -> String {
+        format!(
+            "{}{}{}",
+            self.envs
+                .iter()
+                .format_with("", |(key, val), f| f(&format_args!(
+                    "{}={} ",
+                    key.to_string_lossy(),
+                    shell_escape::escape(val.to_string_lossy()),
+                ))),
+            shell_escape::escape(self.program.to_string_lossy()),
+            self.args.iter().format_with("", |arg, f| f(&format_args!(
+                " {}",
+                shell_escape::escape(arg.to_string_lossy()),
+            ))),
+        )
+    }
 }
 
 impl ProcessBuilder<Present> {
@@ -84,6 +110,7 @@ impl ProcessBuilder<Present> {
     fn spawn(&self, stdout: Stdio) -> anyhow::Result<std::process::Child> {
         let mut child = std::process::Command::new(&self.program)
             .args(&self.args)
+            .envs(self.envs.iter().map(|(k, v)| (k, v)))
             .current_dir(&self.cwd)
             .stdin(if self.pipe_input.is_some() {
                 Stdio::piped()
@@ -107,12 +134,8 @@ impl fmt::Display for ProcessBuilder<Present> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             fmt,
-            "`{}{}`{}",
-            shell_escape::escape(self.program.to_string_lossy()),
-            self.args.iter().format_with("", |arg, f| f(&format_args!(
-                " {}",
-                shell_escape::escape(arg.to_string_lossy()),
-            ))),
+            "`{}`{}",
+            self.to_command_line(),
             if self.display_cwd {
                 format!(" in {}", self.cwd.display())
             } else {
@@ -144,6 +167,7 @@ pub(crate) fn process(program: impl AsRef<Path>) -> ProcessBuilder<NotPresent> {
     ProcessBuilder {
         program: program.as_ref().into(),
         args: vec![],
+        envs: vec![],
         cwd: (),
         display_cwd: false,
         pipe_input: None,
@@ -160,6 +184,7 @@ pub(crate) fn with_which(
     Ok(ProcessBuilder {
         program,
         args: vec![],
+        envs: vec![],
         cwd,
         display_cwd: false,
         pipe_input: None,
@@ -10,7 +10,7 @@ use liquid::object;
 use serde::{de::Error as _, Deserialize, Deserializer};
 use snowchains_core::web::PlatformKind;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     env,
     path::{Path, PathBuf},
     str,
@@ -27,7 +27,14 @@ pub(crate) struct CargoCompeteConfig {
     pub(crate) test_suite: liquid::Template,
     pub(crate) open: Option<String>,
     pub(crate) template: CargoCompeteConfigTempate,
+    #[serde(default)]
+    pub(crate) test: CargoCompeteConfigTest,
     pub(crate) submit_via_binary: Option<CargoCompeteConfigSubmitViaBinary>,
+    /// Show the summary and ask for confirmation before `submit`.
+    #[serde(default)]
+    pub(crate) confirm_before_submit: bool,
+    #[serde(default)]
+    pub(crate) language_id: CargoCompeteConfigLanguageId,
 }
 
 fn deserialize_liquid_template_with_custom_filter<'de, D>(
@@ -110,6 +117,71 @@ where
     }
 }
 
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CargoCompeteConfigTest {
+    /// `match` for test suites that do not have one.
+    pub(crate) default_match: Option<CargoCompeteConfigTestMatch>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub(crate) enum CargoCompeteConfigTestMatch {
+    Exact,
+    Lines,
+    /// `Lines`, but the order of the lines does not matter.
+    UnorderedLines,
+    Float {
+        #[serde(rename = "relative-error")]
+        relative_error: Option<f64>,
+        #[serde(rename = "absolute-error")]
+        absolute_error: Option<f64>,
+    },
+}
+
+impl CargoCompeteConfigTestMatch {
+    /// `match` in the test suite file.
+    ///
+    /// `UnorderedLines` is `Lines` with `unordered-lines: true`. See [`Self::unordered_lines`].
+    pub(crate) fn to_yaml(self) -> serde_yaml::Value {
+        let s = |s: &str| serde_yaml::Value::String(s.to_owned());
+
+        match self {
+            Self::Exact => s("Exact"),
+            Self::Lines | Self::UnorderedLines => s("Lines"),
+            Self::Float {
+                relative_error,
+                absolute_error,
+            } => {
+                let mut float = serde_yaml::Mapping::new();
+                if let Some(relative_error) = relative_error {
+                    float.insert(s("relative_error"), relative_error.into());
+                }
+                if let Some(absolute_error) = absolute_error {
+                    float.insert(s("absolute_error"), absolute_error.into());
+                }
+                let mut r#match = serde_yaml::Mapping::new();
+                r#match.insert(s("Float"), float.into());
+                r#match.into()
+            }
+        }
+    }
+
+    /// `unordered-lines` in the test suite file.
+    pub(crate) fn unordered_lines(self) -> bool {
+        matches!(self, Self::UnorderedLines)
+    }
+}
+
+/// Language IDs for `submit`, which override the default ones.
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CargoCompeteConfigLanguageId {
+    pub(crate) atcoder: Option<String>,
+    pub(crate) codeforces: Option<String>,
+    pub(crate) yukicoder: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct CargoCompeteConfigSubmitViaBinary {
@@ -130,6 +202,8 @@ pub(crate) struct PackageMetadataCargoCompete {
 pub(crate) struct PackageMetadataCargoCompeteBin {
     pub(crate) name: String,
     pub(crate) problem: TargetProblem,
+    /// Overrides `language-id` in `compete.toml`.
+    pub(crate) language_id: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Ord, PartialOrd, Eq, PartialEq)]
@@ -244,68 +318,12 @@ impl Metadata {
     ) -> anyhow::Result<Vec<PathBuf>> {
         let cargo_compete_config = self.read_compete_toml()?;
 
-        let mut package_metadata_cargo_compete_bin = problems
-            .keys()
-            .map(|problem_index| {
-                format!(
-                    r#"{} = {{ name = "", problem = {{ {} }} }}
-"#,
-                    escape_key(&problem_index.to_kebab_case()),
-                    match (
-                        cargo_compete_config.template.platform,
-                        problems_are_yukicoder_no
-                    ) {
-                        (PlatformKind::Atcoder, _) | (PlatformKind::Codeforces, _) => {
-                            r#"platform = "", contest = "", index = "", url = """#
-                        }
-                        (PlatformKind::Yukicoder, true) => {
-                            r#"platform = "", kind = "no", no = "", url = """#
-                        }
-                        (PlatformKind::Yukicoder, false) => {
-                            r#"platform = "", kind = "contest", contest = "", index = "", url = """#
-                        }
-                    }
-                )
-            })
-            .join("")
-            .parse::<toml_edit::Document>()?;
-
-        for (problem_index, problem_url) in problems {
-            package_metadata_cargo_compete_bin[&problem_index.to_kebab_case()]["name"] =
-                toml_edit::value(format!(
-                    "{}-{}",
-                    package_name,
-                    problem_index.to_kebab_case(),
-                ));
-
-            let tbl =
-                &mut package_metadata_cargo_compete_bin[&problem_index.to_kebab_case()]["problem"];
-
-            match cargo_compete_config.template.platform {
-                PlatformKind::Atcoder => {
-                    tbl["platform"] = toml_edit::value("atcoder");
-                    tbl["contest"] = toml_edit::value(package_name);
-                    tbl["index"] = toml_edit::value(&**problem_index);
-                    tbl["url"] = toml_edit::value(problem_url.as_str());
-                }
-                PlatformKind::Codeforces => {
-                    tbl["platform"] = toml_edit::value("codeforces");
-                    tbl["contest"] = toml_edit::value(package_name);
-                    tbl["index"] = toml_edit::value(&**problem_index);
-                    tbl["url"] = toml_edit::value(problem_url.as_str());
-                }
-                PlatformKind::Yukicoder => {
-                    tbl["platform"] = toml_edit::value("yukicoder");
-                    if problems_are_yukicoder_no {
-                        tbl["no"] = toml_edit::value(&**problem_index);
-                    } else {
-                        tbl["contest"] = toml_edit::value(package_name);
-                        tbl["index"] = toml_edit::value(&**problem_index);
-                    }
-                    tbl["url"] = toml_edit::value(problem_url.as_str());
-                }
-            }
-        }
+        let package_metadata_cargo_compete_bin = package_metadata_cargo_compete_bin(
+            &cargo_compete_config,
+            package_name,
+            problems,
+            problems_are_yukicoder_no,
+        )?;
 
         let template_manifest_path = self
             .workspace_root
@@ -341,15 +359,7 @@ impl Metadata {
         manifest["bin"] = toml_edit::Item::ArrayOfTables({
             let mut arr = toml_edit::ArrayOfTables::new();
             for problem_index in problems.keys() {
-                let mut tbl = toml_edit::Table::new();
-                tbl["name"] = toml_edit::value(format!(
-                    "{}-{}",
-                    package_name,
-                    problem_index.to_kebab_case(),
-                ));
-                tbl["path"] =
-                    toml_edit::value(format!("src/bin/{}.rs", problem_index.to_kebab_case()));
-                arr.append(tbl);
+                arr.append(bin_table(package_name, problem_index));
             }
             arr
         });
@@ -357,7 +367,10 @@ impl Metadata {
         let pkg_manifest_dir = self.workspace_root.join(package_name);
 
         if pkg_manifest_dir.exists() {
-            bail!("`{}` already exists", pkg_manifest_dir.display());
+            bail!(
+                "`{}` already exists. add `--append` to add the problems to it",
+                pkg_manifest_dir.display(),
+            );
         }
         crate::fs::create_dir_all(&pkg_manifest_dir)?;
 
@@ -413,34 +426,263 @@ impl Metadata {
             }
         }
 
-        return Ok(src_paths);
+        Ok(src_paths)
+    }
+
+    /// Keys of `package.metadata.cargo-compete.bin` of the package at
+    /// `{workspace_root}/{package_name}`, which may not be a member of the workspace.
+    pub(crate) fn bin_indexes_of(&self, package_name: &str) -> anyhow::Result<BTreeSet<String>> {
+        let manifest = read_existing_manifest(&self.workspace_root.join(package_name))?;
+
+        Ok(manifest["package"]["metadata"]["cargo-compete"]["bin"]
+            .as_table()
+            .map(|bin| bin.iter().map(|(key, _)| key.to_owned()).collect())
+            .unwrap_or_default())
+    }
+
+    /// Appends `problems` to the existing package created by [`add_member`].
+    ///
+    /// [`add_member`]: #method.add_member
+    pub(crate) fn append_to_member(
+        &self,
+        package_name: &str,
+        problems: &BTreeMap<&str, &Url>,
+        problems_are_yukicoder_no: bool,
+        shell: &mut Shell,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        let cargo_compete_config = self.read_compete_toml()?;
+
+        let package_metadata_cargo_compete_bin = package_metadata_cargo_compete_bin(
+            &cargo_compete_config,
+            package_name,
+            problems,
+            problems_are_yukicoder_no,
+        )?;
+
+        let pkg_manifest_dir = self.workspace_root.join(package_name);
+        let pkg_manifest_path = pkg_manifest_dir.join("Cargo.toml");
 
-        fn escape_key(s: &str) -> String {
-            if s.chars().any(|c| c.is_whitespace() || c.is_control()) {
-                return toml::Value::String(s.to_owned()).to_string();
+        let mut manifest = read_existing_manifest(&pkg_manifest_dir)?;
+        append_bins(
+            &mut manifest,
+            package_name,
+            &problems.keys().copied().collect::<Vec<_>>(),
+            &package_metadata_cargo_compete_bin,
+        )?;
+        crate::fs::write(&pkg_manifest_path, manifest.to_string())?;
+        shell.status("Modified", pkg_manifest_path.display())?;
+
+        let src_bin = pkg_manifest_dir.join("src").join("bin");
+        crate::fs::create_dir_all(&src_bin)?;
+
+        let template_code =
+            crate::fs::read_to_string(self.workspace_root.join(cargo_compete_config.template.src))?;
+
+        let src_paths = problems
+            .keys()
+            .map(|problem_index| {
+                src_bin
+                    .join(problem_index.to_kebab_case())
+                    .with_extension("rs")
+            })
+            .collect::<Vec<_>>();
+
+        for src_path in &src_paths {
+            if src_path.exists() {
+                shell.warn(format!("`{}` already exists. skipping", src_path.display()))?;
+            } else {
+                crate::fs::write(src_path, &template_code)?;
             }
+        }
+
+        Ok(src_paths)
+    }
+}
+
+fn read_existing_manifest(pkg_manifest_dir: &Path) -> anyhow::Result<toml_edit::Document> {
+    let pkg_manifest_path = pkg_manifest_dir.join("Cargo.toml");
+
+    if !pkg_manifest_path.exists() {
+        bail!("`{}` does not exist", pkg_manifest_path.display());
+    }
+
+    crate::fs::read_to_string(&pkg_manifest_path)?
+        .parse()
+        .with_context(|| {
+            format!(
+                "could not parse the manifest at `{}`",
+                pkg_manifest_path.display(),
+            )
+        })
+}
 
-            let mut doc = toml_edit::Document::new();
-            doc[s] = toml_edit::value(0);
-            doc.to_string()
-                .trim_end()
-                .trim_end_matches('0')
-                .trim_end()
-                .trim_end_matches('=')
-                .trim_end()
-                .to_owned()
+/// Appends `[[bin]]`s and `package.metadata.cargo-compete.bin.*` to `manifest`.
+fn append_bins(
+    manifest: &mut toml_edit::Document,
+    package_name: &str,
+    problem_indexes: &[&str],
+    package_metadata_cargo_compete_bin: &toml_edit::Document,
+) -> anyhow::Result<()> {
+    if let Some(bins) = manifest["bin"].as_array_of_tables() {
+        for problem_index in problem_indexes {
+            let new_bin = bin_table(package_name, problem_index);
+            for key in &["name", "path"] {
+                let value = new_bin[*key].as_str().unwrap();
+                if bins
+                    .iter()
+                    .any(|bin| bin.get(key).and_then(toml_edit::Item::as_str) == Some(value))
+                {
+                    bail!("`[[bin]]` with `{} = {:?}` already exists", key, value);
+                }
+            }
         }
+    }
 
-        fn set_implicit_table_if_none(item: &mut toml_edit::Item) {
-            if item.is_none() {
-                *item = {
-                    let mut tbl = toml_edit::Table::new();
-                    tbl.set_implicit(true);
-                    toml_edit::Item::Table(tbl)
-                };
+    set_implicit_table_if_none(&mut manifest["package"]["metadata"]);
+    set_implicit_table_if_none(&mut manifest["package"]["metadata"]["cargo-compete"]);
+    set_implicit_table_if_none(&mut manifest["package"]["metadata"]["cargo-compete"]["bin"]);
+
+    for (key, val) in package_metadata_cargo_compete_bin.as_table().iter() {
+        let bin = &mut manifest["package"]["metadata"]["cargo-compete"]["bin"][key];
+        if !bin.is_none() {
+            bail!(
+                "`package.metadata.cargo-compete.bin.{}` already exists",
+                key
+            );
+        }
+        *bin = val.clone();
+
+        // Format only the inline tables to insert, as `add_member` does for a new manifest.
+        if let Some(bin) = bin.as_inline_table_mut() {
+            if let Some(problem) = bin
+                .get_mut("problem")
+                .and_then(toml_edit::Value::as_inline_table_mut)
+            {
+                problem.fmt();
             }
+            bin.fmt();
         }
     }
+
+    if manifest["bin"].is_none() {
+        manifest["bin"] = toml_edit::Item::ArrayOfTables(toml_edit::ArrayOfTables::new());
+    }
+    let arr = manifest["bin"]
+        .as_array_of_tables_mut()
+        .with_context(|| "`bin` is not an array of tables")?;
+    for problem_index in problem_indexes {
+        arr.append(bin_table(package_name, problem_index));
+    }
+    Ok(())
+}
+
+fn package_metadata_cargo_compete_bin(
+    cargo_compete_config: &CargoCompeteConfig,
+    package_name: &str,
+    problems: &BTreeMap<&str, &Url>,
+    problems_are_yukicoder_no: bool,
+) -> anyhow::Result<toml_edit::Document> {
+    let mut package_metadata_cargo_compete_bin = problems
+        .keys()
+        .map(|problem_index| {
+            format!(
+                r#"{} = {{ name = "", problem = {{ {} }} }}
+"#,
+                escape_key(&problem_index.to_kebab_case()),
+                match (
+                    cargo_compete_config.template.platform,
+                    problems_are_yukicoder_no
+                ) {
+                    (PlatformKind::Atcoder, _) | (PlatformKind::Codeforces, _) => {
+                        r#"platform = "", contest = "", index = "", url = """#
+                    }
+                    (PlatformKind::Yukicoder, true) => {
+                        r#"platform = "", kind = "no", no = "", url = """#
+                    }
+                    (PlatformKind::Yukicoder, false) => {
+                        r#"platform = "", kind = "contest", contest = "", index = "", url = """#
+                    }
+                }
+            )
+        })
+        .join("")
+        .parse::<toml_edit::Document>()?;
+
+    for (problem_index, problem_url) in problems {
+        package_metadata_cargo_compete_bin[&problem_index.to_kebab_case()]["name"] =
+            toml_edit::value(format!(
+                "{}-{}",
+                package_name,
+                problem_index.to_kebab_case(),
+            ));
+
+        let tbl =
+            &mut package_metadata_cargo_compete_bin[&problem_index.to_kebab_case()]["problem"];
+
+        match cargo_compete_config.template.platform {
+            PlatformKind::Atcoder => {
+                tbl["platform"] = toml_edit::value("atcoder");
+                tbl["contest"] = toml_edit::value(package_name);
+                tbl["index"] = toml_edit::value(&**problem_index);
+                tbl["url"] = toml_edit::value(problem_url.as_str());
+            }
+            PlatformKind::Codeforces => {
+                tbl["platform"] = toml_edit::value("codeforces");
+                tbl["contest"] = toml_edit::value(package_name);
+                tbl["index"] = toml_edit::value(&**problem_index);
+                tbl["url"] = toml_edit::value(problem_url.as_str());
+            }
+            PlatformKind::Yukicoder => {
+                tbl["platform"] = toml_edit::value("yukicoder");
+                if problems_are_yukicoder_no {
+                    tbl["no"] = toml_edit::value(&**problem_index);
+                } else {
+                    tbl["contest"] = toml_edit::value(package_name);
+                    tbl["index"] = toml_edit::value(&**problem_index);
+                }
+                tbl["url"] = toml_edit::value(problem_url.as_str());
+            }
+        }
+    }
+
+    Ok(package_metadata_cargo_compete_bin)
+}
+
+fn bin_table(package_name: &str, problem_index: &str) -> toml_edit::Table {
+    let mut tbl = toml_edit::Table::new();
+    tbl["name"] = toml_edit::value(format!(
+        "{}-{}",
+        package_name,
+        problem_index.to_kebab_case(),
+    ));
+    tbl["path"] = toml_edit::value(format!("src/bin/{}.rs", problem_index.to_kebab_case()));
+    tbl
+}
+
+fn escape_key(s: &str) -> String {
+    if s.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return toml::Value::String(s.to_owned()).to_string();
+    }
+
+    let mut doc = toml_edit::Document::new();
+    doc[s] = toml_edit::value(0);
+    doc.to_string()
+        .trim_end()
+        .trim_end_matches('0')
+        .trim_end()
+        .trim_end_matches('=')
+        .trim_end()
+        .to_owned()
+}
+
+fn set_implicit_table_if_none(item: &mut toml_edit::Item) {
+    if item.is_none() {
+        *item = {
+            let mut tbl = toml_edit::Table::new();
+            tbl.set_implicit(true);
+            toml_edit::Item::Table(tbl)
+        };
+    }
 }
 
 fn symlink_compete_toml(workspace_root: &Path, pkg_manifest_dir: &Path) -> anyhow::Result<PathBuf> {
@@ -648,6 +890,98 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn append_bins() -> anyhow::Result<()> {
+        let mut manifest = r#"[package]
+name = "abc999"
+version = "0.1.0"
+edition = "2018"
+description = "{\"} }"
+
+[package.metadata.cargo-compete.bin]
+a = { name = "abc999-a", problem = { platform = "atcoder", contest = "abc999", index = "A" } }
+
+[[bin]]
+name = "abc999-a"
+path = "src/bin/a.rs"
+
+[[bin]]
+name = "abc999-c"
+path = "src/bin/c.rs"
+"#
+        .parse::<toml_edit::Document>()?;
+
+        let package_metadata_cargo_compete_bin =
+            r#"b = {name="abc999-b",problem={platform="atcoder",contest="abc999",index="B"}}
+"#
+            .parse::<toml_edit::Document>()?;
+
+        super::append_bins(
+            &mut manifest,
+            "abc999",
+            &["B"],
+            &package_metadata_cargo_compete_bin,
+        )?;
+
+        assert!(manifest.to_string().contains(
+            r#"b = { name = "abc999-b", problem = { platform = "atcoder", contest = "abc999", index = "B" } }
+"#,
+        ));
+
+        let parsed = toml::from_str::<toml::Value>(&manifest.to_string())?;
+
+        assert_eq!(
+            vec!["a", "b"],
+            parsed["package"]["metadata"]["cargo-compete"]["bin"]
+                .as_table()
+                .unwrap()
+                .keys()
+                .map(|k| &**k)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            "B",
+            parsed["package"]["metadata"]["cargo-compete"]["bin"]["b"]["problem"]["index"]
+                .as_str()
+                .unwrap(),
+        );
+        assert_eq!(
+            vec![
+                ("abc999-a", "src/bin/a.rs"),
+                ("abc999-c", "src/bin/c.rs"),
+                ("abc999-b", "src/bin/b.rs"),
+            ],
+            parsed["bin"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|bin| (bin["name"].as_str().unwrap(), bin["path"].as_str().unwrap()))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!("{\"} }", parsed["package"]["description"].as_str().unwrap(),);
+
+        assert!(super::append_bins(
+            &mut manifest,
+            "abc999",
+            &["B"],
+            &package_metadata_cargo_compete_bin,
+        )
+        .is_err());
+
+        let package_metadata_cargo_compete_bin = r#"c = { name = "abc999-c", problem = { platform = "atcoder", contest = "abc999", index = "C" } }
+"#
+        .parse::<toml_edit::Document>()?;
+
+        assert!(super::append_bins(
+            &mut manifest,
+            "abc999",
+            &["C"],
+            &package_metadata_cargo_compete_bin,
+        )
+        .is_err());
+        Ok(())
+    }
+
     #[test]
     fn gen_compete_toml() -> anyhow::Result<()> {
         fn test(platform: PlatformKind, submit_via_binary: bool) -> anyhow::Result<()> {
@@ -1,4 +1,8 @@
 use indicatif::ProgressDrawTarget;
+use prettytable::{
+    format::{FormatBuilder, LinePosition, LineSeparator},
+    Table,
+};
 use snowchains_core::{color_spec, web::StatusCodeColor};
 use std::{
     fmt,
@@ -86,6 +90,20 @@ impl Shell {
         self.output.message_stderr(status, message, color)
     }
 
+    pub(crate) fn clear_screen(&mut self) -> io::Result<()> {
+        if let ShellOut::Stream {
+            stderr,
+            stderr_tty: true,
+            ..
+        } = &mut self.output
+        {
+            stderr.write_all(b"\x1B[2J\x1B[H")?;
+            stderr.flush()?;
+            self.needs_clear = false;
+        }
+        Ok(())
+    }
+
     fn err_erase_line(&mut self) {
         if let ShellOut::Stream {
             stderr,
@@ -184,6 +202,23 @@ impl snowchains_core::web::Shell for Shell {
     }
 }
 
+/// Empty table with box-drawing borders.
+pub(crate) fn table() -> Table {
+    let mut table = Table::new();
+
+    *table.get_format() = FormatBuilder::new()
+        .padding(1, 1)
+        .column_separator('│')
+        .borders('│')
+        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
+        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Intern, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
+        .build();
+
+    table
+}
+
 enum ShellIn {
     Tty,
     PipedStdin,
@@ -0,0 +1,337 @@
+use crate::{bundle::Library, project::CargoCompeteConfigSubmitViaBinary, shell::Shell};
+use anyhow::Context as _;
+use cargo_metadata::{DependencyKind, Metadata, Package, PackageId, Target};
+use liquid::object;
+use semver::Version;
+use std::{collections::HashSet, iter, path::PathBuf};
+
+/// Generates the code to submit for `bin`.
+///
+/// If `submit_via_binary` is present, the code is a program that writes out and executes the
+/// binary built for the target, with `--offline` if `offline` is `true`. Otherwise it is the
+/// source file with the local libraries expanded.
+pub(crate) fn generate(
+    metadata: &Metadata,
+    member: &Package,
+    bin: &Target,
+    submit_via_binary: Option<&CargoCompeteConfigSubmitViaBinary>,
+    offline: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    let CargoCompeteConfigSubmitViaBinary {
+        target,
+        cross,
+        strip,
+        upx,
+    } = match submit_via_binary {
+        Some(submit_via_binary) => submit_via_binary,
+        None => return bundle(metadata, member, bin),
+    };
+
+    let original_source_code = crate::fs::read_to_string(&bin.src_path)?;
+
+    let program = if let Some(cross) = cross {
+        cross.clone()
+    } else {
+        crate::process::cargo_exe()?
+    };
+
+    crate::process::with_which(program, &metadata.workspace_root)?
+        .args(&[
+            "build",
+            "--bin",
+            &bin.name,
+            "--release",
+            "--target",
+            &target,
+        ])
+        .args(if offline { &["--offline"][..] } else { &[] })
+        .cwd(member.manifest_path.parent().unwrap())
+        .display_cwd()
+        .exec_with_shell_status(shell)?;
+
+    let orig_artifact = metadata
+        .target_directory
+        .join(&target)
+        .join("release")
+        .join(&bin.name);
+
+    let artifact = tempfile::Builder::new()
+        .prefix("cargo-compete-exec-base64-encoded-binary-")
+        .tempfile()?
+        .into_temp_path();
+
+    std::fs::copy(orig_artifact, &artifact)?;
+
+    if let Some(strip) = strip {
+        crate::process::with_which(strip, &metadata.workspace_root)?
+            .arg("-s")
+            .arg(&artifact)
+            .exec_with_shell_status(shell)?;
+    }
+
+    if let Some(upx) = upx {
+        crate::process::with_which(upx, &metadata.workspace_root)?
+            .arg("--best")
+            .arg(&artifact)
+            .exec_with_shell_status(shell)?;
+    }
+
+    let artifact_binary = crate::fs::read(&artifact)?;
+
+    artifact.close()?;
+
+    liquid::ParserBuilder::with_stdlib()
+        .build()?
+        .parse(include_str!(
+            "../resources/exec-base64-encoded-binary.rs.liquid"
+        ))?
+        .render(&object!({
+            "source_code": original_source_code,
+            "base64": base64::encode(artifact_binary),
+        }))
+        .map_err(Into::into)
+}
+
+/// Reads the source file of `bin`, and expands the library crates that `member` depends on with
+/// `path` into it.
+pub(crate) fn bundle(
+    metadata: &Metadata,
+    member: &Package,
+    bin: &Target,
+) -> anyhow::Result<String> {
+    let code = crate::fs::read_to_string(&bin.src_path)?;
+
+    let libs = local_libraries(metadata, member)?;
+    if libs.is_empty() {
+        return Ok(code);
+    }
+
+    let libs = libs
+        .iter()
+        .map(|(name, _, src_path)| Library { name, src_path })
+        .collect::<Vec<_>>();
+
+    crate::bundle::bundle(&code, &libs)
+        .with_context(|| format!("could not bundle `{}`", bin.src_path.display()))
+}
+
+/// `member` and the library crates bundled into the code of it.
+pub(crate) fn bundled_packages<'a>(
+    metadata: &'a Metadata,
+    member: &'a Package,
+) -> anyhow::Result<Vec<&'a Package>> {
+    let libs = local_libraries(metadata, member)?;
+    Ok(iter::once(member)
+        .chain(libs.into_iter().map(|(_, package, _)| package))
+        .collect())
+}
+
+/// Library crates that `package` depends on with `path`, recursively.
+///
+/// Returns the names in the code, the packages, and the crate roots.
+fn local_libraries<'a>(
+    metadata: &'a Metadata,
+    package: &'a Package,
+) -> anyhow::Result<Vec<(String, &'a Package, PathBuf)>> {
+    let mut acc = vec![];
+    let mut visited = HashSet::new();
+    collect(metadata, package, &mut acc, &mut visited)?;
+    return Ok(acc);
+
+    fn collect<'a>(
+        metadata: &'a Metadata,
+        package: &'a Package,
+        acc: &mut Vec<(String, &'a Package, PathBuf)>,
+        visited: &mut HashSet<&'a PackageId>,
+    ) -> anyhow::Result<()> {
+        for dependency in &package.dependencies {
+            if dependency.kind != DependencyKind::Normal || dependency.source.is_some() {
+                continue;
+            }
+
+            let dependency_package = match metadata
+                .packages
+                .iter()
+                .find(|p| p.name == dependency.name && p.source.is_none())
+            {
+                Some(dependency_package) => dependency_package,
+                None => continue,
+            };
+
+            if !visited.insert(&dependency_package.id) {
+                continue;
+            }
+
+            let lib = dependency_package
+                .targets
+                .iter()
+                .find(|t| t.kind.iter().any(|k| k == "lib" || k == "rlib"))
+                .with_context(|| {
+                    format!(
+                        "`{}` does not have a `lib` target that can be bundled",
+                        dependency_package.name,
+                    )
+                })?;
+
+            let name = dependency
+                .rename
+                .as_ref()
+                .unwrap_or(&lib.name)
+                .replace('-', "_");
+
+            acc.push((name, dependency_package, lib.src_path.clone()));
+            collect(metadata, dependency_package, acc, visited)?;
+        }
+        Ok(())
+    }
+}
+
+/// A package under the target directory that consists only of the code to submit.
+pub(crate) struct StandalonePackage {
+    pub(crate) manifest_path: PathBuf,
+    pub(crate) target_dir: PathBuf,
+}
+
+/// Writes `code` as the `main.rs` of a standalone package.
+///
+/// If `with_dependencies` is `true`, `dependencies` of `member` and the local libraries are copied
+/// except the ones with `path`, which are expected to be bundled into `code`.
+pub(crate) fn write_standalone_package(
+    metadata: &Metadata,
+    member: &Package,
+    bin_name: &str,
+    code: &str,
+    with_dependencies: bool,
+) -> anyhow::Result<StandalonePackage> {
+    let standalone_dir = metadata
+        .target_directory
+        .join("cargo-compete")
+        .join("as-submitted");
+    let package_dir = standalone_dir.join(bin_name);
+
+    crate::fs::create_dir_all(package_dir.join("src"))?;
+    crate::fs::write(package_dir.join("src").join("main.rs"), code)?;
+    let packages = if with_dependencies {
+        bundled_packages(metadata, member)?
+    } else {
+        vec![]
+    };
+    crate::fs::write(
+        package_dir.join("Cargo.toml"),
+        manifest(member, &packages, bin_name)?,
+    )?;
+
+    // Use the same versions of the dependencies as the workspace.
+    let lockfile = metadata.workspace_root.join("Cargo.lock");
+    if with_dependencies && lockfile.exists() {
+        crate::fs::write(package_dir.join("Cargo.lock"), crate::fs::read(lockfile)?)?;
+    }
+
+    Ok(StandalonePackage {
+        manifest_path: package_dir.join("Cargo.toml"),
+        target_dir: standalone_dir.join("target"),
+    })
+}
+
+/// Checks that `code` compiles by itself with `cargo check`.
+///
+/// If `toolchain` is present, the check is performed with `cargo +<toolchain>`, in a target
+/// directory for the toolchain. If `offline` is `true`, `--offline` is passed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check(
+    metadata: &Metadata,
+    member: &Package,
+    bin_name: &str,
+    code: &str,
+    with_dependencies: bool,
+    toolchain: Option<&Version>,
+    offline: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let StandalonePackage {
+        manifest_path,
+        target_dir,
+    } = write_standalone_package(metadata, member, bin_name, code, with_dependencies)?;
+
+    // Artifacts of different compilers cannot share a target directory.
+    let target_dir = match toolchain {
+        Some(toolchain) => target_dir.with_file_name(format!("target-{}", toolchain)),
+        None => target_dir,
+    };
+
+    let cargo = if let Some(toolchain) = toolchain {
+        // Use the `cargo` proxy of rustup for `+<toolchain>`.
+        crate::process::process(crate::process::which("cargo", &metadata.workspace_root)?)
+            .arg(format!("+{}", toolchain))
+    } else {
+        crate::process::process(crate::process::cargo_exe()?)
+    };
+
+    cargo
+        .arg("check")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(if offline { &["--offline"][..] } else { &[] })
+        .cwd(&metadata.workspace_root)
+        .exec_with_shell_status(shell)
+        .with_context(|| {
+            let main_rs = manifest_path.with_file_name("src").join("main.rs");
+            if let Some(toolchain) = toolchain {
+                format!(
+                    "the code does not compile with Rust {}. see `{}`. if the toolchain is not \
+                     installed, run `rustup toolchain install {}`",
+                    toolchain,
+                    main_rs.display(),
+                    toolchain,
+                )
+            } else {
+                format!(
+                    "the bundled code does not compile. see `{}`",
+                    main_rs.display()
+                )
+            }
+        })
+}
+
+/// Copies the `dependencies` of `packages`. The earlier ones take precedence.
+fn manifest(member: &Package, packages: &[&Package], name: &str) -> anyhow::Result<String> {
+    let mut manifest = r#"[package]
+name = ""
+version = "0.0.0"
+edition = ""
+publish = false
+
+[workspace]
+
+[dependencies]
+"#
+    .parse::<toml_edit::Document>()
+    .unwrap();
+
+    manifest["package"]["name"] = toml_edit::value(name);
+    manifest["package"]["edition"] = toml_edit::value(&*member.edition);
+
+    for package in packages {
+        let package_manifest = crate::fs::read_to_string(&package.manifest_path)?
+            .parse::<toml_edit::Document>()
+            .with_context(|| {
+                format!(
+                    "could not parse the manifest at `{}`",
+                    package.manifest_path.display(),
+                )
+            })?;
+
+        if let Some(dependencies) = package_manifest["dependencies"].as_table() {
+            for (name, dependency) in dependencies.iter() {
+                if dependency["path"].is_none() && manifest["dependencies"][name].is_none() {
+                    manifest["dependencies"][name] = dependency.clone();
+                }
+            }
+        }
+    }
+
+    Ok(manifest.to_string())
+}
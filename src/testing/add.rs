@@ -0,0 +1,194 @@
+use crate::{
+    project::{CargoCompeteConfigTestMatch, PackageExt as _, PackageMetadataCargoCompeteBin},
+    shell::Shell,
+    testing::batch::{self, RunOutput},
+};
+use anyhow::{bail, ensure, Context as _};
+use cargo_metadata::{Metadata, Package};
+use snowchains_core::testsuite::{Additional, BatchTestSuite, TestSuite};
+use std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+pub(crate) struct AddTestcaseArgs<'a> {
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) member: &'a Package,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) package_metadata_bin: &'a PackageMetadataCargoCompeteBin,
+    pub(crate) name: Option<String>,
+    pub(crate) input: String,
+    pub(crate) output: OutputSource<'a>,
+    pub(crate) default_match: Option<CargoCompeteConfigTestMatch>,
+    pub(crate) release: bool,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Where the expected output comes from.
+pub(crate) enum OutputSource<'a> {
+    /// No expected output. The case only checks that the solution exits successfully.
+    None,
+    File(&'a Path),
+    /// Written by the user with `$VISUAL` or `$EDITOR`.
+    Editor,
+    /// Output of a `bin` target that is trusted to be correct.
+    Bin(&'a str),
+}
+
+/// Adds a test case to the `Batch` test suite.
+///
+/// If the test suite consists of text files (`extend`), the case is added as text files in the
+/// same directories. Otherwise it is appended to `cases`.
+pub(crate) fn add_testcase(args: AddTestcaseArgs<'_>) -> anyhow::Result<()> {
+    let AddTestcaseArgs {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        name,
+        input,
+        output,
+        default_match,
+        release,
+        shell,
+    } = args;
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir_utf8(),
+        cargo_compete_config_test_suite,
+        &package_metadata_bin.problem,
+    )?;
+    let test_suite_dir = test_suite_path.parent().unwrap();
+
+    let (cases, extend) =
+        match super::read_test_suite_with_default_match(&test_suite_path, default_match)?.0 {
+            TestSuite::Batch(BatchTestSuite { cases, extend, .. }) => (cases, extend),
+            _ => bail!(
+                "`{}` is not a `Batch` test suite",
+                test_suite_path.display()
+            ),
+        };
+
+    let text_file_dir = if cases.is_empty() {
+        extend.iter().find_map(|additional| match additional {
+            Additional::Text {
+                path, r#in, out, ..
+            } => {
+                text_file_path(test_suite_dir, path, r#in, "")?;
+                text_file_path(test_suite_dir, path, out, "")?;
+                Some((path.clone(), r#in.clone(), out.clone()))
+            }
+        })
+    } else {
+        None
+    };
+
+    let exists = |name: &str| -> bool {
+        cases.iter().any(|c| c.name.as_deref() == Some(name))
+            || text_file_dir.as_ref().map_or(false, |(path, r#in, ..)| {
+                text_file_path(test_suite_dir, path, r#in, name).map_or(false, |p| p.exists())
+            })
+    };
+
+    let name = match name {
+        Some(name) => {
+            ensure!(
+                !exists(&name),
+                "`{}` already exists in `{}`",
+                name,
+                test_suite_path.display(),
+            );
+            name
+        }
+        None => (1..)
+            .map(|i| format!("custom{}", i))
+            .find(|name| !exists(name))
+            .unwrap(),
+    };
+
+    let output = match output {
+        OutputSource::None => None,
+        OutputSource::File(path) => Some(crate::fs::read_to_string(path)?),
+        OutputSource::Editor => Some(edit(&name, &metadata.workspace_root, shell)?),
+        OutputSource::Bin(bin_name) => {
+            let cmd = super::workspace_bin_command(metadata, bin_name, release, shell)?;
+            shell.status(
+                "Running",
+                format!("`{}` to get the expected output", bin_name),
+            )?;
+            let RunOutput { status, stdout, .. } = batch::run(&cmd, &input, None)?;
+            let status = status.unwrap();
+            ensure!(status.success(), "`{}` exited with {}", bin_name, status);
+            Some(stdout)
+        }
+    };
+
+    if let Some((path, r#in, out)) = text_file_dir {
+        let in_path = text_file_path(test_suite_dir, &path, &r#in, &name).unwrap();
+        crate::fs::create_dir_all(in_path.parent().unwrap())?;
+        crate::fs::write(&in_path, &input)?;
+        shell.status("Wrote", in_path.display())?;
+
+        if let Some(output) = output {
+            let out_path = text_file_path(test_suite_dir, &path, &out, &name).unwrap();
+            crate::fs::create_dir_all(out_path.parent().unwrap())?;
+            crate::fs::write(&out_path, output)?;
+            shell.status("Wrote", out_path.display())?;
+        }
+    } else {
+        super::append_batch_test_case(
+            &test_suite_path,
+            default_match,
+            &name,
+            &input,
+            output.as_deref(),
+        )?;
+        shell.status(
+            "Added",
+            format!("`{}` to {}", name, test_suite_path.display()),
+        )?;
+    }
+    Ok(())
+}
+
+/// Path of a text file for `name`, if `pattern` is a simple one like `/in/*.txt`.
+pub(super) fn text_file_path(
+    test_suite_dir: &Path,
+    path: &str,
+    pattern: &str,
+    name: &str,
+) -> Option<PathBuf> {
+    if pattern.matches('*').count() != 1 || pattern.contains(&['?', '[', '{'][..]) {
+        return None;
+    }
+    let path = format!("{}{}", path, pattern.replace('*', name));
+    let path = Path::new(&path);
+    Some(test_suite_dir.join(path.strip_prefix(".").unwrap_or(path)))
+}
+
+/// Lets the user write the expected output with `$VISUAL` or `$EDITOR`.
+fn edit(name: &str, cwd: &Path, shell: &mut Shell) -> anyhow::Result<String> {
+    let editor = env::var_os("VISUAL")
+        .or_else(|| env::var_os("EDITOR"))
+        .unwrap_or_else(|| OsString::from(if cfg!(windows) { "notepad" } else { "vi" }));
+    let editor = editor.to_string_lossy().into_owned();
+    let mut editor = editor.split_whitespace();
+    let program = editor.next().with_context(|| "empty `$EDITOR`")?;
+
+    let tempdir = tempfile::Builder::new()
+        .prefix("cargo-compete-add-testcase-")
+        .tempdir()?;
+    let path = tempdir.path().join(format!("{}.out.txt", name));
+    crate::fs::write(&path, "")?;
+
+    crate::process::with_which(program, cwd)?
+        .args(&editor.collect::<Vec<_>>())
+        .arg(&path)
+        .exec_with_shell_status(shell)?;
+
+    let output = crate::fs::read_to_string(&path)?;
+    tempdir.close()?;
+    Ok(output)
+}
@@ -0,0 +1,50 @@
+use crate::{
+    process::{Present, ProcessBuilder},
+    project::CargoCompeteConfigSubmitViaBinary,
+    shell::Shell,
+    source_code::StandalonePackage,
+};
+use cargo_metadata::{Metadata, Package, Target};
+use std::{env, path::PathBuf};
+
+/// Builds the code that `submit` would send for `bin`, as a standalone package under the target
+/// directory.
+///
+/// The package is built in release mode, as judges do. Returns the artifact and the build command.
+pub(super) fn build(
+    metadata: &Metadata,
+    member: &Package,
+    bin: &Target,
+    submit_via_binary: Option<&CargoCompeteConfigSubmitViaBinary>,
+    shell: &mut Shell,
+) -> anyhow::Result<(PathBuf, ProcessBuilder<Present>)> {
+    let code =
+        crate::source_code::generate(metadata, member, bin, submit_via_binary, false, shell)?;
+
+    let StandalonePackage {
+        manifest_path,
+        target_dir,
+    } = crate::source_code::write_standalone_package(
+        metadata,
+        member,
+        &bin.name,
+        &code,
+        submit_via_binary.is_none(),
+    )?;
+
+    let build = crate::process::process(crate::process::cargo_exe()?)
+        .arg("build")
+        .arg("--release")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .cwd(&metadata.workspace_root);
+    build.exec_with_shell_status(shell)?;
+
+    let artifact = target_dir
+        .join("release")
+        .join(&bin.name)
+        .with_extension(env::consts::EXE_EXTENSION);
+    Ok((artifact, build))
+}
@@ -0,0 +1,594 @@
+use crate::{
+    project::CargoCompeteConfigTestMatch,
+    shell::Shell,
+    testing::{
+        measure::{self, ResourceUsage},
+        outcome::{TestOutcome, Verdict, VerdictKind},
+        sanitize::Sanitizer,
+        TestSuiteExt,
+    },
+};
+use anyhow::{bail, Context as _};
+use cargo_metadata::Metadata;
+use indicatif::{ProgressBar, ProgressStyle};
+use itertools::{EitherOrBoth, Itertools as _};
+use snowchains_core::{
+    judge::CommandExpression,
+    testsuite::{BatchTestCase, BatchTestSuite, ExpectedOutput, Match, PositiveFinite, TestSuite},
+};
+use std::{
+    io::{Read, Write as _},
+    num::NonZeroUsize,
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Timelimit for a checker when the test case does not have one.
+const CHECKER_TIMELIMIT: Duration = Duration::from_secs(10);
+
+/// Judges `cmd` with the test cases, running them in parallel except for Miri.
+///
+/// If `checker` is present, it is invoked as `<checker> <input> <actual output> <expected output>`,
+/// the same order as [testlib](https://github.com/MikeMirzayanov/testlib), and accepts the output
+/// by exiting with 0.
+///
+/// A run is regarded as `MemoryLimitExceeded` if its peak memory exceeds `memory_limit`, or if it
+/// failed to allocate memory.
+///
+/// If `sanitizer` is present, a run with its report is regarded as `SanitizerError`.
+///
+/// Fails if Ctrl-C is pressed.
+pub(crate) fn judge(
+    cmd: &CommandExpression,
+    checker: Option<&CommandExpression>,
+    test_cases: &[BatchTestCase],
+    unordered_lines: bool,
+    memory_limit: Option<u64>,
+    sanitizer: Option<Sanitizer>,
+    shell: &Shell,
+) -> anyhow::Result<TestOutcome> {
+    let _ctrl_c = super::ctrl_c::listen();
+
+    let tempdir = tempfile::Builder::new()
+        .prefix("cargo-compete-judge-")
+        .tempdir()?;
+
+    let progress = ProgressBar::new(test_cases.len() as _);
+    progress.set_draw_target(shell.progress_draw_target());
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{pos}/{len} [{bar:25}] {wide_msg}")
+            .progress_chars("=> "),
+    );
+
+    // `cargo miri run` compiles the `bin` on the first run, and the runs would wait for each other
+    // on the lock of the target directory anyway. Otherwise, run at most one case per CPU so that
+    // the cases do not slow each other down and exceed the timelimits.
+    let num_parallel = if sanitizer == Some(Sanitizer::Miri) {
+        1
+    } else {
+        thread::available_parallelism().map_or(1, NonZeroUsize::get)
+    };
+
+    let mut verdicts = vec![];
+
+    for chunk in test_cases
+        .iter()
+        .enumerate()
+        .collect::<Vec<_>>()
+        .chunks(num_parallel)
+    {
+        if super::ctrl_c::interrupted() {
+            verdicts.push(Err(anyhow::anyhow!("interrupted")));
+            break;
+        }
+
+        let (tempdir, progress) = (tempdir.path(), &progress);
+
+        thread::scope(|scope| {
+            let handles = chunk
+                .iter()
+                .map(|&(i, test_case)| {
+                    scope.spawn(move || {
+                        let name = test_case
+                            .name
+                            .clone()
+                            .unwrap_or_else(|| format!("#{}", i + 1));
+
+                        let verdict = judge_case(
+                            cmd,
+                            checker,
+                            name,
+                            test_case,
+                            unordered_lines,
+                            memory_limit,
+                            sanitizer,
+                            &tempdir.join(i.to_string()),
+                        );
+
+                        progress.inc(1);
+                        if let Ok(verdict) = &verdict {
+                            progress.set_message(&verdict.name);
+                        }
+                        verdict
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            verdicts.extend(handles.into_iter().map(|h| {
+                h.join()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("a judge thread panicked")))
+            }));
+        });
+    }
+
+    progress.finish_and_clear();
+
+    let verdicts = verdicts.into_iter().collect::<anyhow::Result<_>>()?;
+
+    tempdir.close()?;
+    Ok(TestOutcome {
+        verdicts,
+        memory_limit,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn judge_case(
+    cmd: &CommandExpression,
+    checker: Option<&CommandExpression>,
+    name: String,
+    test_case: &BatchTestCase,
+    unordered_lines: bool,
+    memory_limit: Option<u64>,
+    sanitizer: Option<Sanitizer>,
+    tempdir: &Path,
+) -> anyhow::Result<Verdict> {
+    let expected = expected_stdout(&test_case.output).map(ToOwned::to_owned);
+
+    let RunOutput {
+        status,
+        stdout,
+        stderr,
+        elapsed,
+        usage,
+    } = run_with_memory_limit(cmd, &test_case.input, test_case.timelimit, memory_limit)?;
+
+    let sanitizer_report = find_sanitizer_report(sanitizer, &stderr).map(ToOwned::to_owned);
+
+    let (kind, message) = match status {
+        None => (VerdictKind::TimelimitExceeded, None),
+        Some(_) if sanitizer_report.is_some() => (VerdictKind::SanitizerError, sanitizer_report),
+        Some(_) if exceeds_memory_limit(usage, memory_limit) => (
+            VerdictKind::MemoryLimitExceeded,
+            usage
+                .max_rss
+                .map(|n| format!("the peak memory usage was {} bytes", n)),
+        ),
+        Some(status) if !status.success() => (
+            VerdictKind::RuntimeError,
+            Some(format!("the solution exited with {}", status)),
+        ),
+        Some(_) => judge_output(
+            &test_case.output,
+            checker,
+            &test_case.input,
+            &stdout,
+            unordered_lines,
+            test_case.timelimit,
+            tempdir,
+        )?,
+    };
+
+    Ok(Verdict {
+        name,
+        kind,
+        timelimit: test_case.timelimit,
+        elapsed,
+        usage,
+        stdin: (*test_case.input).to_owned(),
+        stdout,
+        stderr,
+        expected,
+        transcript: None,
+        message,
+    })
+}
+
+/// Judges the output of a run that exited successfully, with `checker` if present.
+///
+/// `timelimit` (or [`CHECKER_TIMELIMIT`]) is applied to the checker, and it not finishing within it
+/// is an error.
+fn judge_output(
+    expected: &ExpectedOutput,
+    checker: Option<&CommandExpression>,
+    input: &str,
+    stdout: &str,
+    unordered_lines: bool,
+    timelimit: Option<Duration>,
+    tempdir: &Path,
+) -> anyhow::Result<(VerdictKind, Option<String>)> {
+    let checker = if let Some(checker) = checker {
+        checker
+    } else if accepts(expected, stdout, unordered_lines) {
+        return Ok((VerdictKind::Accepted, None));
+    } else {
+        return Ok((VerdictKind::WrongAnswer, None));
+    };
+
+    crate::fs::create_dir_all(tempdir)?;
+
+    let in_path = tempdir.join("in.txt");
+    let actual_path = tempdir.join("actual.txt");
+    let expected_path = tempdir.join("expected.txt");
+
+    crate::fs::write(&in_path, input)?;
+    crate::fs::write(&actual_path, stdout)?;
+    crate::fs::write(&expected_path, expected_stdout(expected).unwrap_or(""))?;
+
+    let checker = CommandExpression {
+        program: checker.program.clone(),
+        args: checker
+            .args
+            .iter()
+            .cloned()
+            .chain(vec![
+                in_path.into(),
+                actual_path.into(),
+                expected_path.into(),
+            ])
+            .collect(),
+        cwd: checker.cwd.clone(),
+        env: checker.env.clone(),
+    };
+
+    let timelimit = timelimit.unwrap_or(CHECKER_TIMELIMIT);
+    let checker_output = run(&checker, "", Some(timelimit))?;
+    let checker_message = format!("{}{}", checker_output.stdout, checker_output.stderr);
+    let checker_message = Some(checker_message).filter(|s| !s.is_empty());
+
+    match checker_output.status {
+        Some(status) if status.success() => Ok((VerdictKind::Accepted, checker_message)),
+        Some(_) => Ok((VerdictKind::WrongAnswer, checker_message)),
+        None => bail!("the checker did not finish within {:?}", timelimit),
+    }
+}
+
+/// Judges the outputs of the target against the ones of the reference for `stress` and `shrink`,
+/// in the same way as the test suite does.
+pub(super) struct OutputJudge {
+    /// `timelimit` of the test suite.
+    pub(super) timelimit: Option<Duration>,
+    matching: Match,
+    unordered_lines: bool,
+    checker: Option<CommandExpression>,
+}
+
+impl OutputJudge {
+    /// Loads `match`, `unordered-lines`, the checker, and the timelimit of the `Batch` test suite
+    /// at `path`.
+    ///
+    /// If the file does not exist, `default_match` (or `Lines`) is used.
+    pub(super) fn load(
+        path: &Path,
+        default_match: Option<CargoCompeteConfigTestMatch>,
+        metadata: &Metadata,
+        release: bool,
+        shell: &mut Shell,
+    ) -> anyhow::Result<Self> {
+        if !path.exists() {
+            let matching = match default_match {
+                Some(default_match) => serde_yaml::from_value(default_match.to_yaml())?,
+                None => Match::Lines,
+            };
+
+            return Ok(Self {
+                timelimit: None,
+                matching,
+                unordered_lines: default_match.is_some_and(|m| m.unordered_lines()),
+                checker: None,
+            });
+        }
+
+        let (test_suite, test_suite_ext) =
+            super::read_test_suite_with_default_match(path, default_match)?;
+
+        let (timelimit, matching) = match test_suite {
+            TestSuite::Batch(BatchTestSuite {
+                timelimit, r#match, ..
+            }) => (timelimit, r#match),
+            _ => bail!("`{}` is not a `Batch` test suite", path.display()),
+        };
+
+        let checker = match &test_suite_ext.checker {
+            Some(checker) => Some(checker.to_command_expression(
+                metadata,
+                path.parent().unwrap(),
+                release,
+                shell,
+            )?),
+            None => None,
+        };
+
+        Ok(Self {
+            timelimit,
+            matching,
+            unordered_lines: test_suite_ext.unordered_lines,
+            checker,
+        })
+    }
+
+    /// Judges `stdout` of the target that exited successfully, against `expected` from the
+    /// reference.
+    pub(super) fn judge(
+        &self,
+        input: &str,
+        stdout: &str,
+        expected: &str,
+    ) -> anyhow::Result<(VerdictKind, Option<String>)> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("cargo-compete-judge-")
+            .tempdir()?;
+
+        let verdict = judge_output(
+            &expected_output(expected, self.matching),
+            self.checker.as_ref(),
+            input,
+            stdout,
+            self.unordered_lines,
+            self.timelimit,
+            tempdir.path(),
+        )?;
+
+        tempdir.close()?;
+        Ok(verdict)
+    }
+
+    /// Creates an empty `Batch` test suite at `path` with the `match` of this.
+    pub(super) fn create_test_suite(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            crate::fs::create_dir_all(parent)?;
+        }
+
+        super::write_test_suite(
+            path,
+            &TestSuite::Batch(BatchTestSuite {
+                timelimit: self.timelimit,
+                r#match: self.matching,
+                cases: vec![],
+                extend: vec![],
+            }),
+            &TestSuiteExt {
+                unordered_lines: self.unordered_lines,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+pub(super) fn exceeds_memory_limit(usage: ResourceUsage, memory_limit: Option<u64>) -> bool {
+    match (usage.max_rss, memory_limit) {
+        (Some(max_rss), Some(memory_limit)) => max_rss > memory_limit,
+        _ => false,
+    }
+}
+
+pub(super) fn find_sanitizer_report(sanitizer: Option<Sanitizer>, stderr: &str) -> Option<&str> {
+    sanitizer.and_then(|sanitizer| sanitizer.find_report(stderr))
+}
+
+/// Whether `actual` matches `expected`, in the same way as `snowchains_core`.
+///
+/// If `unordered_lines` is `true`, the lines of `Lines` are compared as multisets.
+pub(super) fn accepts(expected: &ExpectedOutput, actual: &str, unordered_lines: bool) -> bool {
+    match expected {
+        ExpectedOutput::Pass => true,
+        ExpectedOutput::Exact { text } => actual == &**text,
+        ExpectedOutput::Lines { text } if unordered_lines => {
+            let mut actual = actual.lines().collect::<Vec<_>>();
+            let mut expected = text.lines().collect::<Vec<_>>();
+            actual.sort_unstable();
+            expected.sort_unstable();
+            actual == expected
+        }
+        ExpectedOutput::Lines { text } => actual.lines().eq(text.lines()),
+        ExpectedOutput::Float {
+            text,
+            relative_error,
+            absolute_error,
+        } => {
+            let relative_error = relative_error.map_or(0.0, PositiveFinite::get);
+            let absolute_error = absolute_error.map_or(0.0, PositiveFinite::get);
+
+            text.lines()
+                .zip_longest(actual.lines())
+                .all(|zip| match zip {
+                    EitherOrBoth::Both(expected, actual) => expected
+                        .split_whitespace()
+                        .zip_longest(actual.split_whitespace())
+                        .all(|zip| match zip {
+                            EitherOrBoth::Both(expected, actual) => {
+                                match (expected.parse::<f64>(), actual.parse::<f64>()) {
+                                    (Ok(expected), Ok(actual)) => {
+                                        (actual - expected).abs() <= absolute_error
+                                            || ((actual - expected) / expected).abs()
+                                                <= relative_error
+                                    }
+                                    _ => expected == actual,
+                                }
+                            }
+                            EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
+                        }),
+                    EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
+                })
+        }
+    }
+}
+
+pub(crate) fn expected_stdout(output: &ExpectedOutput) -> Option<&str> {
+    match output {
+        ExpectedOutput::Pass => None,
+        ExpectedOutput::Exact { text }
+        | ExpectedOutput::Lines { text }
+        | ExpectedOutput::Float { text, .. } => Some(text),
+    }
+}
+
+/// `ExpectedOutput` of `text` with `matching`.
+fn expected_output(text: &str, matching: Match) -> ExpectedOutput {
+    let text = text.into();
+    match matching {
+        Match::Exact => ExpectedOutput::Exact { text },
+        Match::Lines => ExpectedOutput::Lines { text },
+        Match::Float {
+            relative_error,
+            absolute_error,
+        } => ExpectedOutput::Float {
+            text,
+            relative_error,
+            absolute_error,
+        },
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RunOutput {
+    /// `None` if the process was killed for exceeding the timelimit.
+    pub(crate) status: Option<ExitStatus>,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    pub(crate) elapsed: Duration,
+    pub(crate) usage: ResourceUsage,
+}
+
+/// Runs `cmd` with `input`.
+///
+/// Fails if Ctrl-C is pressed while [`super::ctrl_c`] is listening for it.
+pub(crate) fn run(
+    cmd: &CommandExpression,
+    input: &str,
+    timelimit: Option<Duration>,
+) -> anyhow::Result<RunOutput> {
+    run_with_memory_limit(cmd, input, timelimit, None)
+}
+
+/// Runs `cmd` with `input`, limiting its memory with [`spawn`].
+fn run_with_memory_limit(
+    cmd: &CommandExpression,
+    input: &str,
+    timelimit: Option<Duration>,
+    memory_limit: Option<u64>,
+) -> anyhow::Result<RunOutput> {
+    let started = Instant::now();
+    let mut child = spawn(cmd, memory_limit)?;
+
+    let mut stdin = child.stdin.take().unwrap();
+    let input = input.to_owned();
+    let stdin = thread::spawn(move || {
+        let _ = stdin.write_all(input.as_ref());
+    });
+    let stdout = read_to_end(child.stdout.take().unwrap());
+    let stderr = read_to_end(child.stderr.take().unwrap());
+
+    let deadline = timelimit.map(|t| started + t);
+
+    let (status, usage) = loop {
+        if let Some((status, usage)) = measure::try_wait(&mut child)? {
+            break (Some(status), usage);
+        }
+        if deadline.map_or(false, |deadline| Instant::now() > deadline) {
+            let _ = child.kill();
+            let (_, usage) = measure::wait(&mut child)?;
+            break (None, usage);
+        }
+        if super::ctrl_c::interrupted() {
+            let _ = child.kill();
+            measure::wait(&mut child)?;
+            bail!("interrupted");
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    let elapsed = started.elapsed();
+
+    let _ = stdin.join();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    Ok(RunOutput {
+        status,
+        stdout,
+        stderr,
+        elapsed,
+        usage,
+    })
+}
+
+/// Spawns `cmd` with piped stdio.
+///
+/// If `memory_limit` is present, the data segment (the address space on Unix platforms other than
+/// Linux) is limited to twice of it with `setrlimit(2)`. The limit is loose so that the process can
+/// exceed `memory_limit` and be judged by its peak memory usage, while it cannot exhaust the
+/// memory of the machine.
+pub(super) fn spawn(cmd: &CommandExpression, memory_limit: Option<u64>) -> anyhow::Result<Child> {
+    let mut command = measure::command(&cmd.program)
+        .with_context(|| "could not get the path of `cargo-compete` itself")?;
+
+    command
+        .args(&cmd.args)
+        .current_dir(&cmd.cwd)
+        .envs(&cmd.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    if let Some(memory_limit) = memory_limit {
+        limit_memory(&mut command, memory_limit.saturating_mul(2));
+    }
+
+    command.spawn().map_err(|err| {
+        anyhow::Error::from(err).context(format!(
+            "could not execute `{}`",
+            cmd.program.to_string_lossy(),
+        ))
+    })
+}
+
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn limit_memory(command: &mut Command, bytes: u64) {
+    use std::{io, os::unix::process::CommandExt as _};
+
+    let rlimit = libc::rlimit {
+        rlim_cur: bytes as _,
+        rlim_max: bytes as _,
+    };
+
+    // `setrlimit` is async-signal-safe.
+    unsafe {
+        command.pre_exec(move || {
+            #[cfg(target_os = "linux")]
+            let resource = libc::RLIMIT_DATA;
+            #[cfg(not(target_os = "linux"))]
+            let resource = libc::RLIMIT_AS;
+
+            if libc::setrlimit(resource, &rlimit) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_memory(_: &mut Command, _: u64) {}
+
+pub(super) fn read_to_end(mut rdr: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = vec![];
+        let _ = rdr.read_to_end(&mut buf);
+        String::from_utf8_lossy(&buf).into_owned()
+    })
+}
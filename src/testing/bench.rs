@@ -0,0 +1,295 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _, PackageMetadataCargoCompeteBin},
+    shell::Shell,
+    testing::batch::{self, RunOutput},
+};
+use anyhow::{bail, ensure, Context as _};
+use cargo_metadata::{Metadata, Package};
+use prettytable::{cell, row};
+use snowchains_core::{judge::CommandExpression, testsuite::TestSuite};
+use std::{
+    collections::HashSet,
+    env,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub(crate) struct BenchArgs<'a> {
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) member: &'a Package,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) package_metadata_bin: &'a PackageMetadataCargoCompeteBin,
+    /// A `bin` target in the workspace, or a Git revision.
+    pub(crate) against: &'a str,
+    pub(crate) runs: usize,
+    pub(crate) generator: Option<&'a str>,
+    pub(crate) inputs: usize,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Runs the `bin` and `against` over the same inputs in release mode, and prints the median and
+/// the 95th percentile of the elapsed times side by side.
+///
+/// If `against` is not a `bin` target, it is regarded as a Git revision and the `bin` is built from
+/// a temporary worktree.
+pub(crate) fn bench(args: BenchArgs<'_>) -> anyhow::Result<()> {
+    let BenchArgs {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        against,
+        runs,
+        generator,
+        inputs,
+        shell,
+    } = args;
+
+    let bin = member.bin_target(&package_metadata_bin.name)?;
+
+    let current = super::artifact_command(
+        metadata,
+        super::cargo_build_bin(metadata, member, &bin.name, true, shell)?,
+    );
+
+    let is_bin = metadata
+        .all_members()
+        .into_iter()
+        .any(|p| p.bin_target(against).is_ok());
+
+    let against_cmd = if is_bin {
+        super::workspace_bin_command(metadata, against, true, shell)?
+    } else {
+        let artifact = build_revision(metadata, member, &bin.name, against, shell)?;
+        super::artifact_command(metadata, artifact)
+    };
+
+    let inputs = if let Some(generator) = generator {
+        let generator = super::workspace_bin_command(metadata, generator, true, shell)?;
+        (1..=inputs)
+            .map(|seed| {
+                let RunOutput { status, stdout, .. } = batch::run(
+                    &CommandExpression {
+                        program: generator.program.clone(),
+                        args: vec![seed.to_string().into()],
+                        cwd: generator.cwd.clone(),
+                        env: generator.env.clone(),
+                    },
+                    "",
+                    None,
+                )?;
+                let status = status.unwrap();
+                ensure!(status.success(), "the generator exited with {}", status);
+                Ok((format!("seed={}", seed), stdout))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    } else {
+        let test_suite_path = super::test_suite_path(
+            &metadata.workspace_root,
+            member.manifest_dir_utf8(),
+            cargo_compete_config_test_suite,
+            &package_metadata_bin.problem,
+        )?;
+
+        let test_suite = match super::read_test_suite(&test_suite_path)?.0 {
+            TestSuite::Batch(test_suite) => test_suite,
+            _ => bail!(
+                "`{}` is not a `Batch` test suite. use `--generator`",
+                test_suite_path.display(),
+            ),
+        };
+
+        test_suite
+            .load_test_cases(test_suite_path.parent().unwrap(), None::<HashSet<String>>)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, test_case)| {
+                let name = test_case.name.unwrap_or_else(|| format!("#{}", i + 1));
+                (name, (*test_case.input).to_owned())
+            })
+            .collect()
+    };
+
+    shell.status(
+        "Running",
+        format!(
+            "{} input{} {} time{} each",
+            inputs.len(),
+            if inputs.len() == 1 { "" } else { "s" },
+            runs,
+            if runs == 1 { "" } else { "s" },
+        ),
+    )?;
+
+    let mut table = crate::shell::table();
+
+    table.set_titles(row![
+        "Input",
+        format!("{} median", bin.name),
+        format!("{} p95", bin.name),
+        format!("{} median", against),
+        format!("{} p95", against),
+        "Change",
+    ]);
+
+    for (name, input) in &inputs {
+        let (mut current_times, mut against_times) = (vec![], vec![]);
+
+        // Alternate the two so that a drift of the machine affects both equally.
+        for _ in 0..runs {
+            current_times.push(run(&current, &bin.name, name, input)?);
+            against_times.push(run(&against_cmd, against, name, input)?);
+        }
+
+        current_times.sort();
+        against_times.sort();
+
+        let (current_median, against_median) = (
+            percentile(&current_times, 50),
+            percentile(&against_times, 50),
+        );
+
+        table.add_row(row![
+            name,
+            format!("{} ms", current_median.as_millis()),
+            format!("{} ms", percentile(&current_times, 95).as_millis()),
+            format!("{} ms", against_median.as_millis()),
+            format!("{} ms", percentile(&against_times, 95).as_millis()),
+            if against_median > Duration::from_secs(0) {
+                format!(
+                    "{:+.1}%",
+                    (current_median.as_secs_f64() / against_median.as_secs_f64() - 1.0) * 100.0,
+                )
+            } else {
+                "-".to_owned()
+            },
+        ]);
+    }
+
+    write!(shell.err(), "{}", table)?;
+    shell.err().flush()?;
+    Ok(())
+}
+
+fn run(
+    cmd: &CommandExpression,
+    name: &str,
+    input_name: &str,
+    input: &str,
+) -> anyhow::Result<Duration> {
+    let RunOutput {
+        status, elapsed, ..
+    } = batch::run(cmd, input, None)?;
+    let status = status.unwrap();
+    ensure!(
+        status.success(),
+        "`{}` exited with {} for {}",
+        name,
+        status,
+        input_name,
+    );
+    Ok(elapsed)
+}
+
+/// Builds the `bin` at `rev` in a temporary worktree, and returns the artifact.
+///
+/// The artifact is placed in the target directory of the workspace so that it outlives the
+/// worktree, and so that the dependencies are not rebuilt every time.
+fn build_revision(
+    metadata: &Metadata,
+    member: &Package,
+    bin_name: &str,
+    rev: &str,
+    shell: &mut Shell,
+) -> anyhow::Result<PathBuf> {
+    let git = |args: &[&str]| -> anyhow::Result<_> {
+        Ok(crate::process::with_which("git", &metadata.workspace_root)?.args(args))
+    };
+
+    let commit = git(&["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])?
+        .read_with_shell_status(shell)
+        .with_context(|| format!("`{}` is neither a bin target nor a Git revision", rev))?;
+    let commit = commit.trim();
+
+    let toplevel = git(&["rev-parse", "--show-toplevel"])?.read_with_shell_status(shell)?;
+    let toplevel = Path::new(toplevel.trim());
+
+    let manifest_path = member
+        .manifest_path
+        .strip_prefix(toplevel)
+        .with_context(|| {
+            format!(
+                "`{}` is not in `{}`",
+                member.manifest_path.display(),
+                toplevel.display(),
+            )
+        })?;
+
+    let tempdir = tempfile::Builder::new()
+        .prefix("cargo-compete-bench-")
+        .tempdir()?;
+    let worktree = tempdir.path().join("worktree");
+
+    git(&["worktree", "add", "--detach"])?
+        .arg(&worktree)
+        .arg(commit)
+        .exec_with_shell_status(shell)?;
+
+    let target_dir = metadata
+        .target_directory
+        .join("cargo-compete")
+        .join("bench");
+
+    let build = crate::process::process(crate::process::cargo_exe()?)
+        .args(&["build", "--release", "--bin", bin_name])
+        .arg("--manifest-path")
+        .arg(worktree.join(manifest_path))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .cwd(&worktree)
+        .exec_with_shell_status(shell);
+
+    let artifact = target_dir
+        .join("release")
+        .join(bin_name)
+        .with_extension(env::consts::EXE_EXTENSION);
+    let renamed = target_dir
+        .join("release")
+        .join(format!("{}-{}", bin_name, &commit[..commit.len().min(12)]))
+        .with_extension(env::consts::EXE_EXTENSION);
+
+    // Remove the worktree whether the build succeeded or not.
+    git(&["worktree", "remove", "--force"])?
+        .arg(&worktree)
+        .exec_with_shell_status(shell)?;
+    tempdir.close()?;
+
+    build?;
+    std::fs::rename(&artifact, &renamed)
+        .with_context(|| format!("could not rename `{}`", artifact.display()))?;
+    Ok(renamed)
+}
+
+/// Nearest-rank percentile of `sorted`.
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::from_secs(0);
+    }
+    let rank = (p * sorted.len()).div_ceil(100);
+    sorted[rank.max(1) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn percentile() {
+        let ms = Duration::from_millis;
+        let sorted = (1..=20).map(ms).collect::<Vec<_>>();
+        assert_eq!(ms(10), super::percentile(&sorted, 50));
+        assert_eq!(ms(19), super::percentile(&sorted, 95));
+        assert_eq!(ms(7), super::percentile(&[ms(7)], 95));
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{
+    project::{CargoCompeteConfigTestMatch, PackageExt as _, PackageMetadataCargoCompeteBin},
+    shell::Shell,
+    testing::{
+        add::text_file_path,
+        batch::{self, RunOutput},
+        yaml, TestSuiteExt,
+    },
+};
+use anyhow::{bail, ensure};
+use cargo_metadata::{Metadata, Package};
+use snowchains_core::{
+    color_spec,
+    judge::CommandExpression,
+    testsuite::{Additional, BatchTestSuite, TestSuite},
+};
+use std::path::Path;
+use termcolor::Color;
+
+pub(crate) struct BlessArgs<'a> {
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) member: &'a Package,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) package_metadata_bin: &'a PackageMetadataCargoCompeteBin,
+    pub(crate) with: &'a str,
+    pub(crate) default_match: Option<CargoCompeteConfigTestMatch>,
+    pub(crate) release: bool,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Records the outputs of `with` as the expected outputs of the cases that have none, or that are
+/// listed in `pending`.
+pub(crate) fn bless(args: BlessArgs<'_>) -> anyhow::Result<()> {
+    let BlessArgs {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        with,
+        default_match,
+        release,
+        shell,
+    } = args;
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir_utf8(),
+        cargo_compete_config_test_suite,
+        &package_metadata_bin.problem,
+    )?;
+    let test_suite_dir = test_suite_path.parent().unwrap();
+
+    let (mut test_suite, mut test_suite_ext) =
+        super::read_test_suite_with_default_match(&test_suite_path, default_match)?;
+
+    let (cases, extend) = match &mut test_suite {
+        TestSuite::Batch(BatchTestSuite { cases, extend, .. }) => (cases, &*extend),
+        _ => bail!(
+            "`{}` is not a `Batch` test suite",
+            test_suite_path.display()
+        ),
+    };
+
+    let reference = super::workspace_bin_command(metadata, with, release, shell)?;
+
+    let num_pending = test_suite_ext.pending.len();
+    let mut blessed_cases = vec![];
+    let mut num_blessed = 0;
+
+    for (i, case) in cases.iter_mut().enumerate() {
+        let name = case.name.clone().unwrap_or_else(|| format!("#{}", i + 1));
+        if case.out.is_some() && !test_suite_ext.pending.contains(&name) {
+            continue;
+        }
+
+        let output = run(&reference, with, &case.r#in)?;
+        print_diff(&name, case.out.as_deref().unwrap_or(""), &output, shell)?;
+        case.out = Some(output.into());
+        test_suite_ext.pending.retain(|n| *n != name);
+        blessed_cases.push(i);
+        num_blessed += 1;
+    }
+
+    let mut written = vec![];
+
+    for additional in extend {
+        let (path, r#in, out) = match additional {
+            Additional::Text {
+                path, r#in, out, ..
+            } => (path, r#in, out),
+        };
+
+        for name in text_file_names(test_suite_dir, path, r#in)? {
+            let in_path = text_file_path(test_suite_dir, path, r#in, &name).unwrap();
+            let out_path = match text_file_path(test_suite_dir, path, out, &name) {
+                Some(out_path) => out_path,
+                None => bail!("unsupported pattern: {:?}", out),
+            };
+            if out_path.exists() && !test_suite_ext.pending.contains(&name) {
+                continue;
+            }
+
+            let old = if out_path.exists() {
+                crate::fs::read_to_string(&out_path)?
+            } else {
+                "".to_owned()
+            };
+            let output = run(&reference, with, &crate::fs::read_to_string(&in_path)?)?;
+            print_diff(&name, &old, &output, shell)?;
+
+            crate::fs::create_dir_all(out_path.parent().unwrap())?;
+            crate::fs::write(&out_path, output)?;
+            written.push(out_path);
+            test_suite_ext.pending.retain(|n| *n != name);
+            num_blessed += 1;
+        }
+    }
+
+    if num_blessed == 0 {
+        shell.status("Finished", "no test cases to bless")?;
+        return Ok(());
+    }
+
+    // The outputs of the external files do not change the test suite file.
+    if !blessed_cases.is_empty() || test_suite_ext.pending.len() != num_pending {
+        write_test_suite(
+            &test_suite_path,
+            &test_suite,
+            &test_suite_ext,
+            &blessed_cases,
+            test_suite_ext.pending.len() != num_pending,
+        )?;
+        shell.status("Wrote", test_suite_path.display())?;
+    }
+    for path in written {
+        shell.status("Wrote", path.display())?;
+    }
+    shell.status(
+        "Blessed",
+        format!(
+            "{} test case{}",
+            num_blessed,
+            if num_blessed == 1 { "" } else { "s" },
+        ),
+    )?;
+    Ok(())
+}
+
+/// Writes the outputs of `blessed_cases` and `pending` to the test suite file.
+///
+/// The file is edited as text so that comments and formatting are kept. If that fails, the whole
+/// file is re-serialized.
+fn write_test_suite(
+    path: &Path,
+    test_suite: &TestSuite,
+    test_suite_ext: &TestSuiteExt,
+    blessed_cases: &[usize],
+    pending_changed: bool,
+) -> anyhow::Result<()> {
+    let cases = match test_suite {
+        TestSuite::Batch(BatchTestSuite { cases, .. }) => cases,
+        _ => unreachable!(),
+    };
+
+    let edited = blessed_cases
+        .iter()
+        .try_fold(crate::fs::read_to_string(path)?, |yaml, &i| {
+            yaml::set_output(&yaml, i, cases[i].out.as_deref().unwrap())
+        })
+        .and_then(|yaml| {
+            if pending_changed {
+                yaml::set_pending(&yaml, &test_suite_ext.pending)
+            } else {
+                Some(yaml)
+            }
+        })
+        .filter(|edited| {
+            matches!(
+                serde_yaml::from_str(edited).and_then(super::split_test_suite),
+                Ok((TestSuite::Batch(BatchTestSuite { cases: edited_cases, .. }), edited_ext))
+                    if edited_ext.pending == test_suite_ext.pending
+                        && edited_cases
+                            .iter()
+                            .map(|c| c.out.as_deref())
+                            .eq(cases.iter().map(|c| c.out.as_deref()))
+            )
+        });
+
+    if let Some(edited) = edited {
+        crate::fs::write(path, edited)
+    } else {
+        super::write_test_suite(path, test_suite, test_suite_ext)
+    }
+}
+
+fn run(reference: &CommandExpression, name: &str, input: &str) -> anyhow::Result<String> {
+    let RunOutput {
+        status,
+        stdout,
+        stderr,
+        ..
+    } = batch::run(reference, input, None)?;
+    let status = status.unwrap();
+    ensure!(
+        status.success(),
+        "`{}` exited with {}\n{}",
+        name,
+        status,
+        stderr,
+    );
+    Ok(stdout)
+}
+
+/// Names of the text files that match `pattern` such as `/in/*.txt`.
+fn text_file_names(
+    test_suite_dir: &Path,
+    path: &str,
+    pattern: &str,
+) -> anyhow::Result<Vec<String>> {
+    let (dir, file_name) = match pattern.rfind('/') {
+        Some(i) => (&pattern[..i], &pattern[i + 1..]),
+        None => ("", pattern),
+    };
+    let (prefix, suffix) = match (dir.contains('*'), file_name.find('*')) {
+        (false, Some(i)) => (&file_name[..i], &file_name[i + 1..]),
+        _ => bail!("unsupported pattern: {:?}", pattern),
+    };
+
+    let dir = format!("{}{}", path, dir);
+    let dir = Path::new(&dir);
+    let dir = test_suite_dir.join(dir.strip_prefix(".").unwrap_or(dir));
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut names = vec![];
+    for entry in std::fs::read_dir(&dir)? {
+        let file_name = entry?.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name.len() >= prefix.len() + suffix.len()
+            && file_name.starts_with(prefix)
+            && file_name.ends_with(suffix)
+        {
+            names.push(file_name[prefix.len()..file_name.len() - suffix.len()].to_owned());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+fn print_diff(name: &str, old: &str, new: &str, shell: &mut Shell) -> anyhow::Result<()> {
+    let wtr = shell.err();
+
+    wtr.set_color(color_spec!(Bold))?;
+    writeln!(wtr, "{}", name)?;
+    wtr.reset()?;
+
+    for (tag, line) in diff_lines(old, new) {
+        match tag {
+            '-' => wtr.set_color(color_spec!(Fg(Color::Red)))?,
+            '+' => wtr.set_color(color_spec!(Fg(Color::Green)))?,
+            _ => {}
+        }
+        writeln!(wtr, "{}{}", tag, line)?;
+        wtr.reset()?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Line diff based on LCS. Each line is tagged with `-`, `+`, or ` `.
+fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<(char, &'a str)> {
+    let (old, new) = (
+        old.lines().collect::<Vec<_>>(),
+        new.lines().collect::<Vec<_>>(),
+    );
+
+    // Too large to compare. Just replace all of the lines.
+    if old.len().saturating_mul(new.len()) > 4_000_000 {
+        return old
+            .iter()
+            .map(|&l| ('-', l))
+            .chain(new.iter().map(|&l| ('+', l)))
+            .collect();
+    }
+
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut acc = vec![];
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            acc.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            acc.push(('-', old[i]));
+            i += 1;
+        } else {
+            acc.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn diff_lines() {
+        assert_eq!(
+            vec![(' ', "a"), ('-', "b"), ('+', "x"), (' ', "c"), ('+', "d")],
+            super::diff_lines("a\nb\nc\n", "a\nx\nc\nd\n"),
+        );
+        assert_eq!(vec![('+', "1")], super::diff_lines("", "1\n"));
+    }
+}
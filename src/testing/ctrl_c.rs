@@ -0,0 +1,56 @@
+use std::{
+    process,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Once,
+    },
+    thread,
+};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static NUM_LISTENING: AtomicUsize = AtomicUsize::new(0);
+static SPAWN: Once = Once::new();
+
+/// Listens for Ctrl-C with `tokio::signal::ctrl_c` until the returned value is dropped.
+///
+/// While any of the returned values is alive, Ctrl-C does not terminate the process. Check
+/// [`interrupted`] instead. Once all of them are dropped, the flag is reset and Ctrl-C terminates
+/// the process again.
+#[must_use]
+pub(super) fn listen() -> Listening {
+    SPAWN.call_once(|| {
+        thread::spawn(|| {
+            let rt = tokio::runtime::Builder::new()
+                .basic_scheduler()
+                .enable_all()
+                .build();
+            if let Ok(mut rt) = rt {
+                // The handler cannot be unregistered, so emulate the default behavior outside of
+                // `Listening`s.
+                while rt.block_on(tokio::signal::ctrl_c()).is_ok() {
+                    if NUM_LISTENING.load(Ordering::SeqCst) == 0 {
+                        process::exit(130);
+                    }
+                    INTERRUPTED.store(true, Ordering::SeqCst);
+                }
+            }
+        });
+    });
+    NUM_LISTENING.fetch_add(1, Ordering::SeqCst);
+    Listening(())
+}
+
+/// Whether Ctrl-C has been pressed while listening.
+pub(super) fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+pub(super) struct Listening(());
+
+impl Drop for Listening {
+    fn drop(&mut self) {
+        if NUM_LISTENING.fetch_sub(1, Ordering::SeqCst) == 1 {
+            INTERRUPTED.store(false, Ordering::SeqCst);
+        }
+    }
+}
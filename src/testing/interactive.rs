@@ -0,0 +1,216 @@
+use crate::testing::{
+    batch::{exceeds_memory_limit, find_sanitizer_report, read_to_end, spawn},
+    measure::{self, ResourceUsage},
+    outcome::{Verdict, VerdictKind},
+    sanitize::Sanitizer,
+};
+use anyhow::bail;
+use snowchains_core::judge::CommandExpression;
+use std::{
+    io::{Read, Write},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Runs `solution` against `tester`, connecting the stdout of each to the stdin of the other.
+///
+/// The tester decides the verdict with its exit code. Fails if Ctrl-C is pressed.
+pub(crate) fn judge(
+    solution: &CommandExpression,
+    tester: &CommandExpression,
+    timelimit: Option<Duration>,
+    memory_limit: Option<u64>,
+    sanitizer: Option<Sanitizer>,
+) -> anyhow::Result<Verdict> {
+    let _ctrl_c = super::ctrl_c::listen();
+
+    let transcript = Arc::new(Mutex::new(vec![]));
+
+    let mut tester = spawn(tester, None)?;
+    let started = Instant::now();
+    let mut solution = match spawn(solution, memory_limit) {
+        Ok(solution) => solution,
+        Err(err) => {
+            let _ = tester.kill();
+            return Err(err);
+        }
+    };
+
+    let tester_to_solution = relay(
+        tester.stdout.take().unwrap(),
+        solution.stdin.take().unwrap(),
+        Direction::TesterToSolution,
+        transcript.clone(),
+    );
+    let solution_to_tester = relay(
+        solution.stdout.take().unwrap(),
+        tester.stdin.take().unwrap(),
+        Direction::SolutionToTester,
+        transcript.clone(),
+    );
+    let solution_stderr = read_to_end(solution.stderr.take().unwrap());
+    let tester_stderr = read_to_end(tester.stderr.take().unwrap());
+
+    let deadline = timelimit.map(|t| started + t);
+
+    let mut solution_status = None;
+    let mut tester_status = None;
+    let mut elapsed = None;
+    let mut timed_out = false;
+    let mut usage = ResourceUsage::default();
+
+    while solution_status.is_none() || tester_status.is_none() {
+        if solution_status.is_none() {
+            if let Some((status, solution_usage)) = measure::try_wait(&mut solution)? {
+                solution_status = Some(status);
+                usage = solution_usage;
+                elapsed = Some(started.elapsed());
+            }
+        }
+        if tester_status.is_none() {
+            tester_status = measure::try_wait(&mut tester)?.map(|(status, _)| status);
+        }
+        if deadline.map_or(false, |deadline| Instant::now() > deadline) {
+            timed_out = true;
+            if solution_status.is_none() {
+                let _ = solution.kill();
+                let (status, solution_usage) = measure::wait(&mut solution)?;
+                solution_status = Some(status);
+                usage = solution_usage;
+            }
+            let _ = tester.kill();
+            tester_status = Some(measure::wait(&mut tester)?.0);
+            break;
+        }
+        if super::ctrl_c::interrupted() {
+            if solution_status.is_none() {
+                let _ = solution.kill();
+                measure::wait(&mut solution)?;
+            }
+            let _ = tester.kill();
+            measure::wait(&mut tester)?;
+            bail!("interrupted");
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let (solution_status, tester_status) = (solution_status.unwrap(), tester_status.unwrap());
+    let elapsed = elapsed.unwrap_or_else(|| started.elapsed());
+
+    let _ = tester_to_solution.join();
+    let _ = solution_to_tester.join();
+    let solution_stderr = solution_stderr.join().unwrap_or_default();
+    let tester_stderr = tester_stderr.join().unwrap_or_default();
+
+    let transcript = Arc::try_unwrap(transcript)
+        .map(|m| m.into_inner().unwrap())
+        .unwrap_or_default();
+
+    let (kind, message) = if timed_out {
+        (VerdictKind::TimelimitExceeded, None)
+    } else if let Some(report) = find_sanitizer_report(sanitizer, &solution_stderr) {
+        (VerdictKind::SanitizerError, Some(report.to_owned()))
+    } else if exceeds_memory_limit(usage, memory_limit) {
+        (
+            VerdictKind::MemoryLimitExceeded,
+            usage
+                .max_rss
+                .map(|n| format!("the peak memory usage was {} bytes", n)),
+        )
+    } else if !solution_status.success() {
+        (
+            VerdictKind::RuntimeError,
+            Some(format!("the solution exited with {}", solution_status)),
+        )
+    } else if !tester_status.success() {
+        (
+            VerdictKind::WrongAnswer,
+            Some(format!("the tester exited with {}", tester_status)),
+        )
+    } else {
+        (VerdictKind::Accepted, None)
+    };
+
+    let message = match (message, tester_stderr.is_empty()) {
+        (Some(message), false) => Some(format!("{}\n{}", message, tester_stderr)),
+        (message, _) => message,
+    };
+
+    Ok(Verdict {
+        name: "interactive".to_owned(),
+        kind,
+        timelimit,
+        elapsed,
+        usage,
+        stdin: collect(&transcript, Direction::TesterToSolution),
+        stdout: collect(&transcript, Direction::SolutionToTester),
+        stderr: solution_stderr,
+        expected: None,
+        transcript: Some(format_transcript(&transcript)),
+        message,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    TesterToSolution,
+    SolutionToTester,
+}
+
+type Transcript = Vec<(Direction, Vec<u8>)>;
+
+fn relay(
+    mut rdr: impl Read + Send + 'static,
+    mut wtr: impl Write + Send + 'static,
+    direction: Direction,
+    transcript: Arc<Mutex<Transcript>>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buf = [0; 4096];
+        loop {
+            let n = match rdr.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+
+            {
+                let mut transcript = transcript.lock().unwrap();
+                match transcript.last_mut() {
+                    Some((last, chunk)) if *last == direction => chunk.extend_from_slice(&buf[..n]),
+                    _ => transcript.push((direction, buf[..n].to_owned())),
+                }
+            }
+
+            if wtr.write_all(&buf[..n]).and_then(|()| wtr.flush()).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+fn collect(transcript: &[(Direction, Vec<u8>)], direction: Direction) -> String {
+    let bytes = transcript
+        .iter()
+        .filter(|(d, _)| *d == direction)
+        .flat_map(|(_, chunk)| chunk)
+        .copied()
+        .collect::<Vec<_>>();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn format_transcript(transcript: &[(Direction, Vec<u8>)]) -> String {
+    let mut acc = "".to_owned();
+    for (direction, chunk) in transcript {
+        let prefix = match direction {
+            Direction::TesterToSolution => "< ",
+            Direction::SolutionToTester => "> ",
+        };
+        for line in String::from_utf8_lossy(chunk).lines() {
+            acc += prefix;
+            acc += line;
+            acc += "\n";
+        }
+    }
+    acc
+}
@@ -0,0 +1,311 @@
+use std::{
+    env,
+    ffi::OsStr,
+    fs, io,
+    path::PathBuf,
+    process::{self, Child, Command, ExitStatus},
+    time::Duration,
+};
+
+/// Environment variable that makes `cargo-compete` run as the shim instead. See [`command`].
+const SHIM_ENV: &str = "__CARGO_COMPETE_MEASURE_SHIM";
+
+/// Whether the programs are run through the shim.
+const USE_SHIM: bool = cfg!(all(target_os = "linux", not(test)));
+
+/// Resource usage of a child process, reported by `wait4(2)` when it is reaped.
+///
+/// It is always empty on non-Unix platforms.
+#[derive(Default, Clone, Copy, Debug)]
+pub(crate) struct ResourceUsage {
+    /// `ru_utime + ru_stime`.
+    pub(crate) cpu_time: Option<Duration>,
+    /// `ru_maxrss` in bytes.
+    ///
+    /// `ru_maxrss` also counts the pages that the process had before `exec`, that is, the ones of
+    /// `cargo-compete` itself at the time of `fork`. On Linux, the programs are forked from the
+    /// shim instead so that this does not matter. On the other Unix platforms, this may be as
+    /// large as the memory usage of `cargo-compete`.
+    pub(crate) max_rss: Option<u64>,
+}
+
+/// Creates a `Command` for `program`.
+///
+/// On Linux, `program` is run through a shim, that is, `cargo-compete` itself executed with
+/// [`SHIM_ENV`]. The shim is small when it forks, so `ru_maxrss` of the child is of `program`
+/// alone. The shim writes the resource usage to a file, and exits with the same status as
+/// `program`. [`try_wait`] and [`wait`] read the file.
+pub(crate) fn command(program: &OsStr) -> io::Result<Command> {
+    if USE_SHIM {
+        let mut command = Command::new(env::current_exe()?);
+        command.arg(program).env(SHIM_ENV, "1");
+        Ok(command)
+    } else {
+        Ok(Command::new(program))
+    }
+}
+
+/// Runs as the shim and exits, if this process is executed by [`command`].
+pub(crate) fn exit_if_shim() {
+    if env::var_os(SHIM_ENV).is_none() {
+        return;
+    }
+
+    let mut args = env::args_os().skip(1);
+    let program = args.next().unwrap_or_default();
+    shim(&program, args.collect())
+}
+
+#[cfg(target_os = "linux")]
+use self::imp::shim;
+
+#[cfg(not(target_os = "linux"))]
+fn shim(_: &OsStr, _: Vec<std::ffi::OsString>) -> ! {
+    unreachable!("the shim is only used on Linux");
+}
+
+/// Like [`Child::try_wait`], but also returns the resource usage.
+pub(crate) fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, ResourceUsage)>> {
+    match imp::wait(child, true)? {
+        Some((status, _)) if USE_SHIM => Ok(Some((status, read_shim_usage(child.id())?))),
+        ret => Ok(ret),
+    }
+}
+
+/// Like [`Child::wait`], but also returns the resource usage.
+pub(crate) fn wait(child: &mut Child) -> io::Result<(ExitStatus, ResourceUsage)> {
+    let ret = imp::wait(child, false)?.expect("should be reaped");
+    if USE_SHIM {
+        Ok((ret.0, read_shim_usage(child.id())?))
+    } else {
+        Ok(ret)
+    }
+}
+
+/// Path to the file that the shim with `shim_pid` writes to.
+fn shim_usage_path(parent_pid: u32, shim_pid: u32) -> PathBuf {
+    env::temp_dir().join(format!("cargo-compete-usage-{}-{}", parent_pid, shim_pid,))
+}
+
+/// Reads the resource usage written by the shim, which has been reaped.
+///
+/// The usage is unknown if the shim has been killed.
+fn read_shim_usage(shim_pid: u32) -> io::Result<ResourceUsage> {
+    let path = shim_usage_path(process::id(), shim_pid);
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(ResourceUsage::default()),
+        Err(err) => return Err(err),
+    };
+    let _ = fs::remove_file(&path);
+
+    parse_shim_usage(&content)
+}
+
+/// Parses `"{cpu_time_nanos} {max_rss}"` or `"error {message}"`.
+fn parse_shim_usage(content: &str) -> io::Result<ResourceUsage> {
+    if let Some(message) = content.strip_prefix("error ") {
+        return Err(io::Error::other(message));
+    }
+
+    let mut words = content.split_whitespace().map(str::parse::<u64>);
+    match (words.next(), words.next()) {
+        (Some(Ok(cpu_time)), Some(Ok(max_rss))) => Ok(ResourceUsage {
+            cpu_time: Some(Duration::from_nanos(cpu_time)),
+            max_rss: Some(max_rss),
+        }),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid output of the shim: {:?}", content),
+        )),
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::ResourceUsage;
+    use std::{
+        io,
+        mem::MaybeUninit,
+        os::unix::process::ExitStatusExt as _,
+        process::{Child, ExitStatus},
+        time::Duration,
+    };
+
+    #[cfg(target_os = "linux")]
+    use std::ffi::{OsStr, OsString};
+
+    #[cfg(target_os = "linux")]
+    #[allow(unsafe_code)]
+    pub(super) fn shim(program: &OsStr, args: Vec<OsString>) -> ! {
+        use std::{
+            fs,
+            os::unix::process::{parent_id, CommandExt as _},
+            process::{self, Command},
+        };
+
+        let shim_pid = process::id();
+        let path = super::shim_usage_path(parent_id(), shim_pid);
+
+        let mut command = Command::new(program);
+        command.args(args).env_remove(super::SHIM_ENV);
+
+        // Kill `program` when the shim is killed on timeout. `prctl` and `getppid` are
+        // async-signal-safe.
+        unsafe {
+            command.pre_exec(move || {
+                if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                if libc::getppid() as u32 != shim_pid {
+                    return Err(io::Error::from_raw_os_error(libc::ESRCH));
+                }
+                Ok(())
+            });
+        }
+
+        let (status, usage) = match command
+            .spawn()
+            .and_then(|mut child| wait(&mut child, false))
+        {
+            Ok(ret) => ret.expect("should be reaped"),
+            Err(err) => {
+                let message = format!(
+                    "error could not execute `{}`: {}",
+                    program.to_string_lossy(),
+                    err,
+                );
+                let _ = fs::write(&path, message);
+                process::exit(127);
+            }
+        };
+
+        let _ = fs::write(
+            &path,
+            format!(
+                "{} {}",
+                usage.cpu_time.unwrap_or_default().as_nanos(),
+                usage.max_rss.unwrap_or_default(),
+            ),
+        );
+
+        if let Some(signal) = status.signal() {
+            // Terminate with the same signal, without dumping the core again.
+            unsafe {
+                let rlimit = libc::rlimit {
+                    rlim_cur: 0,
+                    rlim_max: 0,
+                };
+                libc::setrlimit(libc::RLIMIT_CORE, &rlimit);
+                libc::signal(signal, libc::SIG_DFL);
+                libc::raise(signal);
+            }
+        }
+        process::exit(status.code().unwrap_or(1))
+    }
+
+    #[allow(unsafe_code)]
+    pub(super) fn wait(
+        child: &mut Child,
+        nohang: bool,
+    ) -> io::Result<Option<(ExitStatus, ResourceUsage)>> {
+        let options = if nohang { libc::WNOHANG } else { 0 };
+
+        let mut status = 0;
+        let mut rusage = MaybeUninit::<libc::rusage>::zeroed();
+
+        loop {
+            let pid =
+                unsafe { libc::wait4(child.id() as _, &mut status, options, rusage.as_mut_ptr()) };
+
+            match pid {
+                -1 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err);
+                    }
+                }
+                0 => return Ok(None),
+                _ => break,
+            }
+        }
+
+        // `wait4` has filled it.
+        let rusage = unsafe { rusage.assume_init() };
+
+        // `ru_maxrss` is in kilobytes except on macOS.
+        let max_rss = rusage.ru_maxrss as u64;
+        let max_rss = if cfg!(target_os = "macos") {
+            max_rss
+        } else {
+            max_rss * 1024
+        };
+
+        let usage = ResourceUsage {
+            cpu_time: Some(duration(rusage.ru_utime) + duration(rusage.ru_stime)),
+            max_rss: Some(max_rss),
+        };
+        Ok(Some((ExitStatus::from_raw(status), usage)))
+    }
+
+    fn duration(tv: libc::timeval) -> Duration {
+        Duration::from_secs(tv.tv_sec as _) + Duration::from_micros(tv.tv_usec as _)
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use super::ResourceUsage;
+    use std::{
+        io,
+        process::{Child, ExitStatus},
+    };
+
+    pub(super) fn wait(
+        child: &mut Child,
+        nohang: bool,
+    ) -> io::Result<Option<(ExitStatus, ResourceUsage)>> {
+        let status = if nohang {
+            child.try_wait()?
+        } else {
+            Some(child.wait()?)
+        };
+        Ok(status.map(|status| (status, ResourceUsage::default())))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::{
+        process::{Command, Stdio},
+        time::Duration,
+    };
+
+    #[test]
+    fn wait() -> anyhow::Result<()> {
+        let mut child = Command::new("sh")
+            .args(&["-c", "exit 3"])
+            .stdout(Stdio::null())
+            .spawn()?;
+        let (status, usage) = super::wait(&mut child)?;
+        assert_eq!(Some(3), status.code());
+        assert!(usage.max_rss.map_or(false, |n| n > 0));
+        assert!(usage.cpu_time.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_shim_usage() -> anyhow::Result<()> {
+        let usage = super::parse_shim_usage("401000 2596864")?;
+        assert_eq!(Some(Duration::from_micros(401)), usage.cpu_time);
+        assert_eq!(Some(2_596_864), usage.max_rss);
+
+        let err = super::parse_shim_usage("error could not execute `a`").unwrap_err();
+        assert_eq!("could not execute `a`", err.to_string());
+
+        assert!(super::parse_shim_usage("").is_err());
+        Ok(())
+    }
+}
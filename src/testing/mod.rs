@@ -0,0 +1,792 @@
+mod add;
+mod as_submitted;
+mod batch;
+mod bench;
+mod bless;
+mod ctrl_c;
+mod interactive;
+mod measure;
+mod outcome;
+mod sanitize;
+mod shrink;
+mod stress;
+mod watch;
+mod yaml;
+
+pub(crate) use self::{
+    add::{add_testcase, AddTestcaseArgs, OutputSource},
+    bench::{bench, BenchArgs},
+    bless::{bless, BlessArgs},
+    measure::exit_if_shim,
+    outcome::ReportFormat,
+    sanitize::Sanitizer,
+    shrink::{shrink, ShrinkArgs},
+    stress::{stress, StressArgs},
+    watch::watch,
+};
+
+use crate::{
+    process::{Present, ProcessBuilder},
+    project::{
+        CargoCompeteConfigSubmitViaBinary, CargoCompeteConfigTestMatch, MetadataExt as _,
+        PackageExt as _, PackageMetadataCargoCompeteBin, TargetProblem, TargetProblemYukicoder,
+    },
+    shell::Shell,
+    testing::outcome::{RepeatedOutcome, TestOutcome},
+};
+use anyhow::{bail, ensure, Context as _};
+use az::SaturatingAs as _;
+use cargo_metadata::{Metadata, Package};
+use human_size::{Byte, Size};
+use liquid::object;
+use maplit::btreemap;
+use serde::{Deserialize, Serialize};
+use snowchains_core::{
+    judge::CommandExpression,
+    testsuite::{
+        BatchTestCase, BatchTestSuite, ExpectedOutput, InteractiveTestSuite, PartialBatchTestCase,
+        TestSuite,
+    },
+};
+use std::{
+    collections::HashSet,
+    env,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub(crate) struct Args<'a> {
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) member: &'a Package,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) package_metadata_bin: &'a PackageMetadataCargoCompeteBin,
+    pub(crate) default_match: Option<CargoCompeteConfigTestMatch>,
+    pub(crate) release: bool,
+    pub(crate) test_case_names: Option<HashSet<String>>,
+    pub(crate) display_limit: Size,
+    /// Overrides `memorylimit` in the test suite.
+    pub(crate) memory_limit: Option<Size>,
+    /// Ignores the memory limit.
+    pub(crate) no_memory_limit: bool,
+    /// Tests the code that `submit` would send instead of the `bin` itself.
+    pub(crate) as_submitted: bool,
+    pub(crate) submit_via_binary: Option<&'a CargoCompeteConfigSubmitViaBinary>,
+    /// Builds and runs the `bin` with the sanitizer. The memory limit is not applied.
+    pub(crate) sanitizer: Option<Sanitizer>,
+    /// Number of times to run each case. If it is greater than 1, statistics of the runs are
+    /// reported instead of the verdicts.
+    pub(crate) repeat: usize,
+    pub(crate) format: ReportFormat,
+    pub(crate) report_path: Option<&'a Path>,
+    pub(crate) shell: &'a mut Shell,
+}
+
+pub(crate) fn test(args: Args<'_>) -> anyhow::Result<()> {
+    let Args {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        default_match,
+        release,
+        test_case_names,
+        display_limit,
+        memory_limit,
+        no_memory_limit,
+        as_submitted,
+        submit_via_binary,
+        sanitizer,
+        repeat,
+        format,
+        report_path,
+        shell,
+    } = args;
+
+    let bin = member.bin_target(&package_metadata_bin.name)?;
+
+    let (solution, build) = if let Some(sanitizer) = sanitizer {
+        sanitizer.build(metadata, member, &bin.name, shell)?
+    } else if as_submitted {
+        let (artifact, build) =
+            as_submitted::build(metadata, member, bin, submit_via_binary, shell)?;
+        (artifact_command(metadata, artifact), build)
+    } else {
+        let build = cargo_build_bin_command(metadata, member, &[&*bin.name], release)?;
+        build.exec_with_shell_status(shell)?;
+        let artifact = bin_artifact(metadata, &bin.name, release)?;
+        (artifact_command(metadata, artifact), build)
+    };
+
+    if repeat > 1 {
+        // Build the checker or the tester, and read the test cases only once.
+        let problem = prepare_problem(
+            metadata,
+            member,
+            cargo_compete_config_test_suite,
+            package_metadata_bin,
+            default_match,
+            release,
+            test_case_names,
+            memory_limit,
+            no_memory_limit,
+            sanitizer,
+            shell,
+        )?;
+
+        let mut runs = vec![];
+        for i in 0..repeat {
+            shell.status("Repeating", format!("{}/{}", i + 1, repeat))?;
+            runs.push(problem.judge(&solution, sanitizer, shell)?);
+        }
+
+        let outcome = RepeatedOutcome { runs };
+        outcome.report(
+            format,
+            report_path,
+            &bin.name,
+            &build.to_command_line(),
+            shell,
+        )?;
+        return outcome.error_on_fail();
+    }
+
+    let outcome = judge_problem(
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        solution,
+        default_match,
+        release,
+        test_case_names,
+        memory_limit,
+        no_memory_limit,
+        sanitizer,
+        shell,
+    )?;
+
+    let display_limit = display_limit.into::<Byte>().value().saturating_as();
+
+    outcome.report(
+        format,
+        report_path,
+        &bin.name,
+        &build.to_command_line(),
+        display_limit,
+        shell,
+    )?;
+    outcome.error_on_fail()
+}
+
+pub(crate) struct ProblemsArgs<'a> {
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) member: &'a Package,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) package_metadata_bins: Vec<&'a PackageMetadataCargoCompeteBin>,
+    pub(crate) default_match: Option<CargoCompeteConfigTestMatch>,
+    pub(crate) release: bool,
+    pub(crate) display_limit: Size,
+    pub(crate) memory_limit: Option<Size>,
+    pub(crate) no_memory_limit: bool,
+    pub(crate) format: ReportFormat,
+    pub(crate) report_path: Option<&'a Path>,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Tests several problems, building all of the `bin`s with one `cargo build`.
+pub(crate) fn test_problems(args: ProblemsArgs<'_>) -> anyhow::Result<()> {
+    let ProblemsArgs {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bins,
+        default_match,
+        release,
+        display_limit,
+        memory_limit,
+        no_memory_limit,
+        format,
+        report_path,
+        shell,
+    } = args;
+
+    let bin_names = package_metadata_bins
+        .iter()
+        .map(|b| Ok(&*member.bin_target(&b.name)?.name))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let build = cargo_build_bin_command(metadata, member, &bin_names, release)?;
+    build.exec_with_shell_status(shell)?;
+
+    let display_limit = display_limit.into::<Byte>().value().saturating_as();
+
+    let mut outcomes = vec![];
+
+    // Keep the flag set after a judge is interrupted, so that the loop stops.
+    let _ctrl_c = ctrl_c::listen();
+
+    for package_metadata_bin in package_metadata_bins {
+        shell.status("Testing", &package_metadata_bin.name)?;
+
+        // Keep testing the other problems even if this one cannot be judged.
+        let outcome =
+            bin_artifact(metadata, &package_metadata_bin.name, release).and_then(|artifact| {
+                judge_problem(
+                    metadata,
+                    member,
+                    cargo_compete_config_test_suite,
+                    package_metadata_bin,
+                    artifact_command(metadata, artifact),
+                    default_match,
+                    release,
+                    None,
+                    memory_limit,
+                    no_memory_limit,
+                    None,
+                    shell,
+                )
+            });
+
+        match &outcome {
+            Ok(outcome) if format == ReportFormat::Pretty => {
+                outcome.report(
+                    format,
+                    None,
+                    &package_metadata_bin.name,
+                    &build.to_command_line(),
+                    display_limit,
+                    shell,
+                )?;
+                writeln!(shell.err())?;
+            }
+            Ok(_) => {}
+            Err(_) if ctrl_c::interrupted() => bail!("interrupted"),
+            Err(err) => {
+                shell.warn(format!(
+                    "could not test `{}`: {:#}",
+                    package_metadata_bin.name, err,
+                ))?;
+            }
+        }
+
+        outcomes.push((&*package_metadata_bin.name, outcome));
+    }
+
+    outcome::report_problems(
+        &outcomes,
+        format,
+        report_path,
+        &build.to_command_line(),
+        display_limit,
+        shell,
+    )
+}
+
+/// Judges `solution` that has already been built for the `bin`.
+#[allow(clippy::too_many_arguments)]
+fn judge_problem(
+    metadata: &Metadata,
+    member: &Package,
+    cargo_compete_config_test_suite: &liquid::Template,
+    package_metadata_bin: &PackageMetadataCargoCompeteBin,
+    solution: CommandExpression,
+    default_match: Option<CargoCompeteConfigTestMatch>,
+    release: bool,
+    test_case_names: Option<HashSet<String>>,
+    memory_limit: Option<Size>,
+    no_memory_limit: bool,
+    sanitizer: Option<Sanitizer>,
+    shell: &mut Shell,
+) -> anyhow::Result<TestOutcome> {
+    let problem = prepare_problem(
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        default_match,
+        release,
+        test_case_names,
+        memory_limit,
+        no_memory_limit,
+        sanitizer,
+        shell,
+    )?;
+    problem.judge(&solution, sanitizer, shell)
+}
+
+/// Test suite of a problem, loaded with the checker or the tester built.
+enum PreparedProblem {
+    Batch {
+        test_cases: Vec<BatchTestCase>,
+        checker: Option<CommandExpression>,
+        unordered_lines: bool,
+        memory_limit: Option<u64>,
+    },
+    Interactive {
+        tester: CommandExpression,
+        timelimit: Option<Duration>,
+        memory_limit: Option<u64>,
+    },
+    Unsubmittable,
+}
+
+/// Loads the test suite for the `bin`, and builds the checker or the tester.
+#[allow(clippy::too_many_arguments)]
+fn prepare_problem(
+    metadata: &Metadata,
+    member: &Package,
+    cargo_compete_config_test_suite: &liquid::Template,
+    package_metadata_bin: &PackageMetadataCargoCompeteBin,
+    default_match: Option<CargoCompeteConfigTestMatch>,
+    release: bool,
+    test_case_names: Option<HashSet<String>>,
+    memory_limit: Option<Size>,
+    no_memory_limit: bool,
+    sanitizer: Option<Sanitizer>,
+    shell: &mut Shell,
+) -> anyhow::Result<PreparedProblem> {
+    let test_suite_path = test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir_utf8(),
+        cargo_compete_config_test_suite,
+        &package_metadata_bin.problem,
+    )?;
+
+    let (test_suite, test_suite_ext) =
+        read_test_suite_with_default_match(&test_suite_path, default_match)?;
+
+    // Sanitizers and Miri use far more memory than the solution itself.
+    let memory_limit = if no_memory_limit || sanitizer.is_some() {
+        None
+    } else if let Some(memory_limit) = memory_limit {
+        Some(memory_limit.into::<Byte>().value().saturating_as())
+    } else {
+        test_suite_ext.memory_limit()?
+    };
+
+    match test_suite {
+        TestSuite::Batch(test_suite) => {
+            let mut test_cases =
+                test_suite.load_test_cases(test_suite_path.parent().unwrap(), test_case_names)?;
+            for test_case in &mut test_cases {
+                if matches!(&test_case.name, Some(name) if test_suite_ext.pending.contains(name)) {
+                    test_case.output = ExpectedOutput::Pass;
+                }
+                // Miri is too slow to be compared with the timelimit.
+                if sanitizer == Some(Sanitizer::Miri) {
+                    test_case.timelimit = None;
+                }
+            }
+
+            let checker = match &test_suite_ext.checker {
+                Some(checker) => Some(checker.to_command_expression(
+                    metadata,
+                    test_suite_path.parent().unwrap(),
+                    release,
+                    shell,
+                )?),
+                None => None,
+            };
+
+            Ok(PreparedProblem::Batch {
+                test_cases,
+                checker,
+                unordered_lines: test_suite_ext.unordered_lines,
+                memory_limit,
+            })
+        }
+        TestSuite::Interactive(InteractiveTestSuite { timelimit, .. }) => {
+            let tester = test_suite_ext
+                .tester
+                .with_context(|| {
+                    format!(
+                        "`tester` is required for `Interactive` problems. add it to `{}`",
+                        test_suite_path.display(),
+                    )
+                })?
+                .to_command_expression(
+                    metadata,
+                    test_suite_path.parent().unwrap(),
+                    release,
+                    shell,
+                )?;
+
+            Ok(PreparedProblem::Interactive {
+                tester,
+                timelimit: timelimit.filter(|_| sanitizer != Some(Sanitizer::Miri)),
+                memory_limit,
+            })
+        }
+        TestSuite::Unsubmittable => {
+            shell.warn("this is `Unsubmittable` problem")?;
+            Ok(PreparedProblem::Unsubmittable)
+        }
+    }
+}
+
+impl PreparedProblem {
+    fn judge(
+        &self,
+        solution: &CommandExpression,
+        sanitizer: Option<Sanitizer>,
+        shell: &mut Shell,
+    ) -> anyhow::Result<TestOutcome> {
+        match self {
+            Self::Batch {
+                test_cases,
+                checker,
+                unordered_lines,
+                memory_limit,
+            } => {
+                shell.status(
+                    "Judging",
+                    format!(
+                        "{} test case{}",
+                        test_cases.len(),
+                        if test_cases.len() == 1 { "" } else { "s" },
+                    ),
+                )?;
+
+                batch::judge(
+                    solution,
+                    checker.as_ref(),
+                    test_cases,
+                    *unordered_lines,
+                    *memory_limit,
+                    sanitizer,
+                    shell,
+                )
+            }
+            Self::Interactive {
+                tester,
+                timelimit,
+                memory_limit,
+            } => {
+                shell.status("Running", "the solution against the tester")?;
+
+                Ok(TestOutcome {
+                    verdicts: vec![interactive::judge(
+                        solution,
+                        tester,
+                        *timelimit,
+                        *memory_limit,
+                        sanitizer,
+                    )?],
+                    memory_limit: *memory_limit,
+                })
+            }
+            Self::Unsubmittable => Ok(TestOutcome {
+                verdicts: vec![],
+                memory_limit: None,
+            }),
+        }
+    }
+}
+
+/// Properties of a test suite file that `snowchains_core` does not know.
+#[derive(Default, Deserialize, Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct TestSuiteExt {
+    /// Program that communicates with the solution in `Interactive` problems.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tester: Option<ExternalProgram>,
+    /// Program that decides verdicts in place of the exact match, for `Batch` problems.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) checker: Option<ExternalProgram>,
+    /// Memory limit of the problem in a human-readable form such as `1024 MB`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) memorylimit: Option<String>,
+    /// Names of the cases whose expected outputs are placeholders. Their outputs are not checked
+    /// until they are blessed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) pending: Vec<String>,
+    /// Compares the lines regardless of their order. Only for `match: Lines`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) unordered_lines: bool,
+}
+
+impl TestSuiteExt {
+    const KEYS: &'static [&'static str] = &[
+        "tester",
+        "checker",
+        "memorylimit",
+        "pending",
+        "unordered-lines",
+    ];
+
+    /// Parses `memorylimit` into bytes.
+    pub(crate) fn memory_limit(&self) -> anyhow::Result<Option<u64>> {
+        self.memorylimit
+            .as_ref()
+            .map(|memorylimit| {
+                let size = memorylimit
+                    .parse::<Size>()
+                    .map_err(|_| anyhow::anyhow!("invalid `memorylimit`: {:?}", memorylimit))?;
+                Ok(size.into::<Byte>().value().saturating_as())
+            })
+            .transpose()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ExternalProgram {
+    /// A `bin` target in the workspace.
+    Bin(String),
+    /// An arbitrary command, run in the directory of the test suite.
+    Command(Vec<String>),
+}
+
+impl ExternalProgram {
+    pub(crate) fn to_command_expression(
+        &self,
+        metadata: &Metadata,
+        test_suite_dir: &Path,
+        release: bool,
+        shell: &mut Shell,
+    ) -> anyhow::Result<CommandExpression> {
+        match self {
+            Self::Bin(name) => workspace_bin_command(metadata, name, release, shell),
+            Self::Command(args) => {
+                let (program, args) = args
+                    .split_first()
+                    .with_context(|| "`command` must not be empty")?;
+
+                Ok(CommandExpression {
+                    program: crate::process::which(program, test_suite_dir)?.into(),
+                    args: args.iter().map(Into::into).collect(),
+                    cwd: test_suite_dir.to_owned(),
+                    env: btreemap!(),
+                })
+            }
+        }
+    }
+}
+
+pub(crate) fn read_test_suite(path: &Path) -> anyhow::Result<(TestSuite, TestSuiteExt)> {
+    read_test_suite_with_default_match(path, None)
+}
+
+/// Reads the test suite, applying `default_match` if it has no `match`.
+///
+/// `match` and `unordered-lines` of `default_match` are put in the suite and its extension, so
+/// they are kept when the suite is written back.
+pub(super) fn read_test_suite_with_default_match(
+    path: &Path,
+    default_match: Option<CargoCompeteConfigTestMatch>,
+) -> anyhow::Result<(TestSuite, TestSuiteExt)> {
+    let mut test_suite = crate::fs::read_yaml::<serde_yaml::Mapping, _>(path)?;
+
+    let key = |s: &str| serde_yaml::Value::String(s.to_owned());
+    let default_match = default_match.filter(|_| {
+        test_suite.get(&key("type")) == Some(&key("Batch"))
+            && !test_suite.contains_key(&key("match"))
+    });
+    if let Some(default_match) = default_match {
+        test_suite.insert(key("match"), default_match.to_yaml());
+        if default_match.unordered_lines() {
+            test_suite.insert(key("unordered-lines"), true.into());
+        }
+    }
+
+    split_test_suite(test_suite)
+        .with_context(|| format!("could not parse the YAML file at `{}`", path.display()))
+}
+
+/// Splits the properties of [`TestSuiteExt`] from the ones `snowchains_core` knows.
+fn split_test_suite(
+    mut test_suite: serde_yaml::Mapping,
+) -> serde_yaml::Result<(TestSuite, TestSuiteExt)> {
+    let mut ext = serde_yaml::Mapping::new();
+    for &key in TestSuiteExt::KEYS {
+        let key = serde_yaml::Value::String(key.to_owned());
+        if let Some(value) = test_suite.remove(&key) {
+            ext.insert(key, value);
+        }
+    }
+
+    Ok((
+        serde_yaml::from_value(serde_yaml::Value::Mapping(test_suite))?,
+        serde_yaml::from_value(serde_yaml::Value::Mapping(ext))?,
+    ))
+}
+
+pub(crate) fn write_test_suite(
+    path: &Path,
+    test_suite: &TestSuite,
+    test_suite_ext: &TestSuiteExt,
+) -> anyhow::Result<()> {
+    let mut content = test_suite.to_yaml_pretty();
+
+    if let serde_yaml::Value::Mapping(ext) = serde_yaml::to_value(test_suite_ext)? {
+        if !ext.is_empty() {
+            if !content.ends_with('\n') {
+                content += "\n";
+            }
+            content += serde_yaml::to_string(&ext)?.trim_start_matches("---\n");
+            content += "\n";
+        }
+    }
+
+    crate::fs::write(path, content)
+}
+
+/// Appends a named case to the `Batch` test suite at `path`.
+///
+/// The file is edited as text so that comments and formatting are kept. If that fails, the whole
+/// file is re-serialized.
+pub(crate) fn append_batch_test_case(
+    path: &Path,
+    default_match: Option<CargoCompeteConfigTestMatch>,
+    name: &str,
+    input: &str,
+    output: Option<&str>,
+) -> anyhow::Result<()> {
+    let (mut test_suite, test_suite_ext) = read_test_suite_with_default_match(path, default_match)?;
+
+    if let TestSuite::Batch(BatchTestSuite { cases, .. }) = &mut test_suite {
+        ensure!(
+            cases.iter().all(|c| c.name.as_deref() != Some(name)),
+            "`{}` already exists in `{}`",
+            name,
+            path.display(),
+        );
+
+        let num_cases = cases.len();
+
+        let edited = yaml::append_case(&crate::fs::read_to_string(path)?, name, input, output)
+            .filter(|edited| {
+                matches!(
+                    serde_yaml::from_str(edited).and_then(split_test_suite),
+                    Ok((TestSuite::Batch(BatchTestSuite { cases, .. }), _))
+                        if cases.len() == num_cases + 1
+                            && cases.last().map_or(false, |c| {
+                                c.name.as_deref() == Some(name)
+                                    && *c.r#in == *input
+                                    && c.out.as_deref() == output
+                            })
+                )
+            });
+
+        if let Some(edited) = edited {
+            return crate::fs::write(path, edited);
+        }
+
+        cases.push(PartialBatchTestCase {
+            name: Some(name.to_owned()),
+            r#in: input.into(),
+            out: output.map(Into::into),
+            timelimit: None,
+            r#match: None,
+        });
+    } else {
+        bail!("`{}` is not a `Batch` test suite", path.display());
+    }
+
+    write_test_suite(path, &test_suite, &test_suite_ext)
+}
+
+pub(crate) fn workspace_bin_command(
+    metadata: &Metadata,
+    bin_name: &str,
+    release: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<CommandExpression> {
+    let package = metadata
+        .all_members()
+        .into_iter()
+        .find(|p| p.bin_target(bin_name).is_ok())
+        .with_context(|| format!("no bin target named `{}` in the workspace", bin_name))?;
+
+    let artifact = cargo_build_bin(metadata, package, bin_name, release, shell)?;
+
+    Ok(CommandExpression {
+        program: artifact.into(),
+        args: vec![],
+        cwd: metadata.workspace_root.clone(),
+        env: btreemap!(),
+    })
+}
+
+fn cargo_build_bin(
+    metadata: &Metadata,
+    package: &Package,
+    bin_name: &str,
+    release: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<PathBuf> {
+    cargo_build_bin_command(metadata, package, &[bin_name], release)?
+        .exec_with_shell_status(shell)?;
+    bin_artifact(metadata, bin_name, release)
+}
+
+fn cargo_build_bin_command(
+    metadata: &Metadata,
+    package: &Package,
+    bin_names: &[&str],
+    release: bool,
+) -> anyhow::Result<ProcessBuilder<Present>> {
+    Ok(crate::process::process(crate::process::cargo_exe()?)
+        .arg("build")
+        .args(
+            &bin_names
+                .iter()
+                .flat_map(|&bin_name| vec!["--bin", bin_name])
+                .collect::<Vec<_>>(),
+        )
+        .args(if release { &["--release"] } else { &[] })
+        .arg("--manifest-path")
+        .arg(&package.manifest_path)
+        .cwd(&metadata.workspace_root))
+}
+
+fn artifact_command(metadata: &Metadata, artifact: PathBuf) -> CommandExpression {
+    CommandExpression {
+        program: artifact.into(),
+        args: vec![],
+        cwd: metadata.workspace_root.clone(),
+        env: btreemap!(),
+    }
+}
+
+fn bin_artifact(metadata: &Metadata, bin_name: &str, release: bool) -> anyhow::Result<PathBuf> {
+    let artifact = metadata
+        .target_directory
+        .join(if release { "release" } else { "debug" })
+        .join(bin_name)
+        .with_extension(env::consts::EXE_EXTENSION);
+
+    ensure!(artifact.exists(), "`{}` does not exist", artifact.display());
+    Ok(artifact)
+}
+
+pub(crate) fn test_suite_path(
+    workspace_root: &Path,
+    pkg_manifest_dir: &str,
+    cargo_compete_config_test_suite: &liquid::Template,
+    target_problem: &TargetProblem,
+) -> anyhow::Result<PathBuf> {
+    let (contest, problem) = match target_problem {
+        TargetProblem::Atcoder { contest, index, .. }
+        | TargetProblem::Codeforces { contest, index, .. }
+        | TargetProblem::Yukicoder(TargetProblemYukicoder::Contest { contest, index, .. }) => {
+            (&**contest, index.clone())
+        }
+        TargetProblem::Yukicoder(TargetProblemYukicoder::Problem { no, .. }) => {
+            ("problems", no.to_string())
+        }
+    };
+
+    let vars = object!({
+        "manifest_dir": pkg_manifest_dir,
+        "contest": contest,
+        "problem": problem,
+    });
+
+    let test_suite_path = cargo_compete_config_test_suite.render(&vars)?;
+    let test_suite_path = Path::new(&test_suite_path);
+    let test_suite_path = test_suite_path
+        .strip_prefix(".")
+        .unwrap_or(&test_suite_path);
+    Ok(workspace_root.join(test_suite_path))
+}
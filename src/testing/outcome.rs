@@ -0,0 +1,853 @@
+use crate::{shell::Shell, testing::measure::ResourceUsage};
+use anyhow::bail;
+use az::SaturatingAs as _;
+use prettytable::{cell, row};
+use serde_json::json;
+use snowchains_core::color_spec;
+use std::{collections::HashSet, fmt, io, path::Path, time::Duration};
+use strum::{EnumString, EnumVariantNames};
+use termcolor::{Color, WriteColor};
+
+#[derive(EnumString, EnumVariantNames, Clone, Copy, Debug, PartialEq, Eq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ReportFormat {
+    Pretty,
+    Json,
+    Junit,
+}
+
+#[derive(Debug)]
+pub(crate) struct TestOutcome {
+    pub(crate) verdicts: Vec<Verdict>,
+    /// Memory limit of the problem in bytes.
+    pub(crate) memory_limit: Option<u64>,
+}
+
+impl TestOutcome {
+    pub(crate) fn print_pretty(
+        &self,
+        mut wtr: impl WriteColor,
+        display_limit: Option<usize>,
+    ) -> io::Result<()> {
+        let num_verdicts = self.verdicts.len();
+
+        for (i, verdict) in self.verdicts.iter().enumerate() {
+            if i > 0 {
+                writeln!(wtr)?;
+            }
+
+            write!(wtr, "{}/{} ({:?}) ", i + 1, num_verdicts, verdict.name)?;
+            wtr.set_color(color_spec!(Bold, Fg(verdict.kind.color())))?;
+            write!(wtr, "{}", verdict.kind)?;
+            wtr.reset()?;
+            writeln!(wtr, " ({} ms)", verdict.elapsed.as_millis())?;
+
+            if verdict.kind == VerdictKind::Accepted {
+                continue;
+            }
+
+            if let Some(transcript) = &verdict.transcript {
+                write_section(&mut wtr, "transcript", transcript, display_limit)?;
+            } else {
+                write_section(&mut wtr, "stdin", &verdict.stdin, display_limit)?;
+                if let Some(expected) = &verdict.expected {
+                    write_section(&mut wtr, "expected", expected, display_limit)?;
+                }
+                write_section(&mut wtr, "actual", &verdict.stdout, display_limit)?;
+            }
+            if !verdict.stderr.is_empty() {
+                write_section(&mut wtr, "stderr", &verdict.stderr, display_limit)?;
+            }
+            if let Some(message) = &verdict.message {
+                write_section(&mut wtr, "message", message, display_limit)?;
+            }
+        }
+
+        if !self.verdicts.is_empty() {
+            writeln!(wtr)?;
+            self.write_summary(&mut wtr)?;
+        }
+
+        return wtr.flush();
+
+        fn write_section(
+            mut wtr: impl WriteColor,
+            title: &str,
+            content: &str,
+            display_limit: Option<usize>,
+        ) -> io::Result<()> {
+            wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+            writeln!(wtr, "{}:", title)?;
+            wtr.reset()?;
+
+            match display_limit {
+                Some(display_limit) if content.len() > display_limit => {
+                    let mut end = display_limit;
+                    while !content.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    writeln!(wtr, "{}", &content[..end])?;
+                    wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                    writeln!(wtr, "<{} bytes omitted>", content.len() - end)?;
+                    wtr.reset()
+                }
+                _ => {
+                    write!(wtr, "{}", content)?;
+                    if !content.ends_with('\n') {
+                        wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                        writeln!(wtr, "<missing EOL>")?;
+                        wtr.reset()?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    fn write_summary(&self, mut wtr: impl WriteColor) -> io::Result<()> {
+        let mut table = crate::shell::table();
+
+        table.set_titles(row!["Case", "Verdict", "Wall", "CPU", "Max RSS"]);
+
+        for verdict in &self.verdicts {
+            table.add_row(row![
+                verdict.name,
+                verdict.kind,
+                format!("{} ms", verdict.elapsed.as_millis()),
+                verdict
+                    .usage
+                    .cpu_time
+                    .map(|t| format!("{} ms", t.as_millis()))
+                    .unwrap_or_else(|| "-".to_owned()),
+                verdict
+                    .usage
+                    .max_rss
+                    .map(format_bytes)
+                    .unwrap_or_else(|| "-".to_owned()),
+            ]);
+        }
+
+        write!(wtr, "{}", table)?;
+
+        if let Some(slowest) = self.slowest() {
+            write!(
+                wtr,
+                "Slowest: {:?} ({} ms)",
+                slowest.name,
+                slowest.elapsed.as_millis(),
+            )?;
+            if let Some(headroom) = self.time_headroom_ms() {
+                write!(wtr, ", headroom against the timelimit: {} ms", headroom)?;
+            }
+            writeln!(wtr)?;
+        }
+
+        if let (Some(peak), Some(headroom)) = (self.max_rss(), self.memory_headroom_bytes()) {
+            writeln!(
+                wtr,
+                "Peak memory: {}, headroom against the memory limit: {}{}",
+                format_bytes(peak),
+                if headroom < 0 { "-" } else { "" },
+                format_bytes(headroom.abs().saturating_as()),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn slowest(&self) -> Option<&Verdict> {
+        self.verdicts.iter().max_by_key(|v| v.elapsed)
+    }
+
+    fn max_rss(&self) -> Option<u64> {
+        self.verdicts.iter().flat_map(|v| v.usage.max_rss).max()
+    }
+
+    /// Timelimit minus the elapsed time of the slowest case. Negative if it exceeded.
+    fn time_headroom_ms(&self) -> Option<i64> {
+        let slowest = self.slowest()?;
+        let timelimit = slowest.timelimit?;
+        Some(
+            timelimit.as_millis().saturating_as::<i64>()
+                - slowest.elapsed.as_millis().saturating_as::<i64>(),
+        )
+    }
+
+    /// Memory limit minus the peak memory usage. Negative if it exceeded.
+    fn memory_headroom_bytes(&self) -> Option<i64> {
+        Some(self.memory_limit?.saturating_as::<i64>() - self.max_rss()?.saturating_as::<i64>())
+    }
+
+    /// Prints the outcome in `format`.
+    ///
+    /// `Pretty` is always written to stderr. The other formats are written to `report_path`, or
+    /// to stdout if it is absent.
+    pub(crate) fn report(
+        &self,
+        format: ReportFormat,
+        report_path: Option<&Path>,
+        name: &str,
+        build_command: &str,
+        display_limit: usize,
+        shell: &mut Shell,
+    ) -> anyhow::Result<()> {
+        let content = match format {
+            ReportFormat::Pretty => {
+                writeln!(shell.err())?;
+                return self
+                    .print_pretty(shell.err(), Some(display_limit))
+                    .map_err(Into::into);
+            }
+            ReportFormat::Json => {
+                serde_json::to_string_pretty(&self.to_json(name, build_command, display_limit))?
+            }
+            ReportFormat::Junit => self.to_junit_xml(name, build_command, display_limit),
+        };
+
+        write_report(content, report_path, shell)
+    }
+
+    pub(crate) fn to_json(
+        &self,
+        name: &str,
+        build_command: &str,
+        display_limit: usize,
+    ) -> serde_json::Value {
+        json!({
+            "name": name,
+            "build_command": build_command,
+            "passed": self.verdicts.iter().filter(|v| v.kind == VerdictKind::Accepted).count(),
+            "failed": self.verdicts.iter().filter(|v| v.kind != VerdictKind::Accepted).count(),
+            "memorylimit_bytes": self.memory_limit,
+            "summary": {
+                "slowest": self.slowest().map(|v| &v.name),
+                "max_rss_bytes": self.max_rss(),
+                "time_headroom_ms": self.time_headroom_ms(),
+                "memory_headroom_bytes": self.memory_headroom_bytes(),
+            },
+            "cases": self
+                .verdicts
+                .iter()
+                .map(|v| {
+                    json!({
+                        "name": v.name,
+                        "verdict": v.kind.to_string(),
+                        "elapsed_ms": v.elapsed.as_millis().saturating_as::<u64>(),
+                        "timelimit_ms": v.timelimit.map(|t| t.as_millis().saturating_as::<u64>()),
+                        "cpu_time_ms": v.usage.cpu_time.map(|t| t.as_millis().saturating_as::<u64>()),
+                        "max_rss_bytes": v.usage.max_rss,
+                        "input": excerpt(&v.stdin, display_limit),
+                        "expected": v.expected.as_ref().map(|s| excerpt(s, display_limit)),
+                        "actual": excerpt(&v.stdout, display_limit),
+                        "stderr": excerpt(&v.stderr, display_limit),
+                        "transcript": v.transcript.as_ref().map(|s| excerpt(s, display_limit)),
+                        "message": v.message,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    pub(crate) fn to_junit_xml(
+        &self,
+        name: &str,
+        build_command: &str,
+        display_limit: usize,
+    ) -> String {
+        let mut xml = r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned();
+        xml += "\n";
+        xml += &self.to_junit_testsuite(name, build_command, display_limit);
+        xml
+    }
+
+    fn to_junit_testsuite(&self, name: &str, build_command: &str, display_limit: usize) -> String {
+        let num_failures = self
+            .verdicts
+            .iter()
+            .filter(|v| v.kind != VerdictKind::Accepted)
+            .count();
+        let total_time = self.verdicts.iter().map(|v| v.elapsed).sum::<Duration>();
+
+        let mut xml = format!(
+            r#"<testsuite name="{}" tests="{}" failures="{}" time="{:.3}">"#,
+            xml_escape(name),
+            self.verdicts.len(),
+            num_failures,
+            total_time.as_secs_f64(),
+        );
+        xml += "\n";
+        xml += "  <properties>\n";
+        xml += &format!(
+            r#"    <property name="build_command" value="{}"/>"#,
+            xml_escape(build_command),
+        );
+        xml += "\n  </properties>\n";
+
+        for verdict in &self.verdicts {
+            xml += &format!(
+                r#"  <testcase name="{}" classname="{}" time="{:.3}">"#,
+                xml_escape(&verdict.name),
+                xml_escape(name),
+                verdict.elapsed.as_secs_f64(),
+            );
+            xml += "\n";
+
+            if verdict.kind != VerdictKind::Accepted {
+                let mut body = format!("input:\n{}\n", excerpt(&verdict.stdin, display_limit));
+                if let Some(expected) = &verdict.expected {
+                    body += &format!("expected:\n{}\n", excerpt(expected, display_limit));
+                }
+                body += &format!("actual:\n{}\n", excerpt(&verdict.stdout, display_limit));
+                if let Some(message) = &verdict.message {
+                    body += &format!("message:\n{}\n", message);
+                }
+
+                xml += &format!(
+                    r#"    <failure type="{}" message="{}">{}</failure>"#,
+                    xml_escape(&verdict.kind.to_string()),
+                    xml_escape(&verdict.kind.to_string()),
+                    xml_escape(&body),
+                );
+                xml += "\n";
+            }
+
+            if !verdict.stderr.is_empty() {
+                xml += &format!(
+                    "    <system-err>{}</system-err>\n",
+                    xml_escape(&excerpt(&verdict.stderr, display_limit)),
+                );
+            }
+
+            xml += "  </testcase>\n";
+        }
+
+        xml += "</testsuite>";
+        xml
+    }
+
+    fn is_success(&self) -> bool {
+        self.verdicts
+            .iter()
+            .all(|v| v.kind == VerdictKind::Accepted)
+    }
+
+    pub(crate) fn error_on_fail(&self) -> anyhow::Result<()> {
+        let num_failures = self
+            .verdicts
+            .iter()
+            .filter(|v| v.kind != VerdictKind::Accepted)
+            .count();
+
+        if num_failures > 0 {
+            bail!(
+                "{}/{} test{} failed",
+                num_failures,
+                self.verdicts.len(),
+                if num_failures == 1 { "" } else { "s" },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Prints the outcomes of several problems in `format`, and fails if any of them failed.
+///
+/// A problem that could not be judged, e.g. because its test suite is missing, is reported with
+/// the error instead of aborting the others.
+///
+/// For `Pretty`, only the summary is printed since each outcome is expected to have been printed
+/// already.
+pub(crate) fn report_problems(
+    outcomes: &[(&str, anyhow::Result<TestOutcome>)],
+    format: ReportFormat,
+    report_path: Option<&Path>,
+    build_command: &str,
+    display_limit: usize,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let num_failed = outcomes
+        .iter()
+        .filter(|(_, o)| !o.as_ref().map_or(false, TestOutcome::is_success))
+        .count();
+
+    match format {
+        ReportFormat::Pretty => {
+            let mut table = crate::shell::table();
+
+            table.set_titles(row!["Problem", "Passed", "Failed", "Slowest"]);
+
+            for (name, outcome) in outcomes {
+                match outcome {
+                    Ok(outcome) => {
+                        let num_passed = outcome
+                            .verdicts
+                            .iter()
+                            .filter(|v| v.kind == VerdictKind::Accepted)
+                            .count();
+                        table.add_row(row![
+                            name,
+                            num_passed,
+                            outcome.verdicts.len() - num_passed,
+                            outcome
+                                .slowest()
+                                .map(|v| format!("{} ms", v.elapsed.as_millis()))
+                                .unwrap_or_else(|| "-".to_owned()),
+                        ]);
+                    }
+                    Err(_) => {
+                        table.add_row(row![name, "error", "-", "-"]);
+                    }
+                }
+            }
+
+            write!(shell.err(), "{}", table)?;
+            shell.err().flush()?;
+        }
+        ReportFormat::Json => {
+            let content = serde_json::to_string_pretty(&json!({
+                "build_command": build_command,
+                "passed": outcomes.len() - num_failed,
+                "failed": num_failed,
+                "problems": outcomes
+                    .iter()
+                    .map(|(name, o)| match o {
+                        Ok(o) => o.to_json(name, build_command, display_limit),
+                        Err(err) => json!({
+                            "name": name,
+                            "build_command": build_command,
+                            "error": format!("{:#}", err),
+                        }),
+                    })
+                    .collect::<Vec<_>>(),
+            }))?;
+            write_report(content, report_path, shell)?;
+        }
+        ReportFormat::Junit => {
+            let mut xml = r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned();
+            xml += "\n<testsuites>\n";
+            for (name, outcome) in outcomes {
+                match outcome {
+                    Ok(outcome) => {
+                        xml += &outcome.to_junit_testsuite(name, build_command, display_limit);
+                    }
+                    Err(err) => {
+                        xml += &format!(
+                            r#"<testsuite name="{}" tests="0" failures="0" errors="1" time="0.000">"#,
+                            xml_escape(name),
+                        );
+                        xml += &format!(
+                            "\n  <error message=\"{}\"/>\n</testsuite>",
+                            xml_escape(&format!("{:#}", err)),
+                        );
+                    }
+                }
+                xml += "\n";
+            }
+            xml += "</testsuites>";
+            write_report(xml, report_path, shell)?;
+        }
+    }
+
+    if num_failed > 0 {
+        bail!(
+            "{}/{} problem{} failed",
+            num_failed,
+            outcomes.len(),
+            if num_failed == 1 { "" } else { "s" },
+        );
+    }
+    Ok(())
+}
+
+/// Outcomes of running the same test suite several times.
+#[derive(Debug)]
+pub(crate) struct RepeatedOutcome {
+    pub(crate) runs: Vec<TestOutcome>,
+}
+
+impl RepeatedOutcome {
+    /// Statistics of each case, in the order of the first run.
+    fn cases(&self) -> Vec<CaseStats<'_>> {
+        let mut cases = Vec::<(&str, Vec<&Verdict>)>::new();
+        for verdict in self.runs.iter().flat_map(|run| &run.verdicts) {
+            match cases.iter_mut().find(|(name, _)| *name == verdict.name) {
+                Some((_, verdicts)) => verdicts.push(verdict),
+                None => cases.push((&verdict.name, vec![verdict])),
+            }
+        }
+
+        cases
+            .into_iter()
+            .map(|(name, verdicts)| CaseStats::new(name, &verdicts))
+            .collect()
+    }
+
+    /// Prints the statistics in `format`.
+    pub(crate) fn report(
+        &self,
+        format: ReportFormat,
+        report_path: Option<&Path>,
+        name: &str,
+        build_command: &str,
+        shell: &mut Shell,
+    ) -> anyhow::Result<()> {
+        let cases = self.cases();
+
+        let content = match format {
+            ReportFormat::Pretty => {
+                let mut table = crate::shell::table();
+
+                table.set_titles(row![
+                    "Case", "Passed", "Mean", "Stddev", "Min", "Max", "Output"
+                ]);
+
+                for case in &cases {
+                    table.add_row(row![
+                        case.name,
+                        format!("{}/{}", case.passed, case.runs),
+                        format!("{:.1} ms", case.mean_ms),
+                        format!("{:.1} ms", case.stddev_ms),
+                        format!("{} ms", case.min_ms),
+                        format!("{} ms", case.max_ms),
+                        if case.outputs_differ {
+                            "differs"
+                        } else {
+                            "stable"
+                        },
+                    ]);
+                }
+
+                let wtr = shell.err();
+                writeln!(wtr)?;
+                write!(wtr, "{}", table)?;
+
+                for case in cases.iter().filter(|c| c.is_failure()) {
+                    wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+                    write!(wtr, "{:?}", case.name)?;
+                    wtr.reset()?;
+                    if case.passed < case.runs {
+                        write!(
+                            wtr,
+                            " failed in {}/{} runs",
+                            case.runs - case.passed,
+                            case.runs,
+                        )?;
+                    }
+                    if let Some(kind) = case.first_failure {
+                        write!(wtr, " (first as {})", kind)?;
+                    }
+                    if case.outputs_differ {
+                        write!(wtr, " produced different outputs between runs")?;
+                    }
+                    writeln!(wtr)?;
+                }
+                wtr.flush()?;
+                return Ok(());
+            }
+            ReportFormat::Json => serde_json::to_string_pretty(&json!({
+                "name": name,
+                "build_command": build_command,
+                "repeat": self.runs.len(),
+                "cases": cases
+                    .iter()
+                    .map(|case| {
+                        json!({
+                            "name": case.name,
+                            "passed": case.passed,
+                            "runs": case.runs,
+                            "pass_rate": case.passed as f64 / case.runs as f64,
+                            "mean_ms": case.mean_ms,
+                            "stddev_ms": case.stddev_ms,
+                            "min_ms": case.min_ms,
+                            "max_ms": case.max_ms,
+                            "outputs_differ": case.outputs_differ,
+                            "first_failure": case.first_failure.map(|k| k.to_string()),
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            }))?,
+            ReportFormat::Junit => {
+                let mut xml = r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned();
+                xml += "\n";
+                xml += &format!(
+                    r#"<testsuite name="{}" tests="{}" failures="{}">"#,
+                    xml_escape(name),
+                    cases.len(),
+                    cases.iter().filter(|c| c.is_failure()).count(),
+                );
+                xml += "\n  <properties>\n";
+                xml += &format!(
+                    r#"    <property name="build_command" value="{}"/>"#,
+                    xml_escape(build_command),
+                );
+                xml += "\n";
+                xml += &format!(
+                    r#"    <property name="repeat" value="{}"/>"#,
+                    self.runs.len(),
+                );
+                xml += "\n  </properties>\n";
+
+                for case in &cases {
+                    xml += &format!(
+                        r#"  <testcase name="{}" classname="{}" time="{:.3}">"#,
+                        xml_escape(case.name),
+                        xml_escape(name),
+                        case.mean_ms / 1000.0,
+                    );
+                    xml += "\n";
+                    if case.is_failure() {
+                        let message = format!(
+                            "passed {}/{} runs{}",
+                            case.passed,
+                            case.runs,
+                            if case.outputs_differ {
+                                ", outputs differ between runs"
+                            } else {
+                                ""
+                            },
+                        );
+                        xml += &format!(
+                            r#"    <failure type="{}" message="{}"/>"#,
+                            xml_escape(
+                                &case
+                                    .first_failure
+                                    .map(|k| k.to_string())
+                                    .unwrap_or_else(|| "Unstable Output".to_owned()),
+                            ),
+                            xml_escape(&message),
+                        );
+                        xml += "\n";
+                    }
+                    xml += "  </testcase>\n";
+                }
+
+                xml += "</testsuite>";
+                xml
+            }
+        };
+
+        write_report(content, report_path, shell)
+    }
+
+    pub(crate) fn error_on_fail(&self) -> anyhow::Result<()> {
+        let cases = self.cases();
+        let num_flaky = cases.iter().filter(|c| c.is_failure()).count();
+
+        if num_flaky > 0 {
+            bail!(
+                "{}/{} test{} failed or produced different outputs in {} runs",
+                num_flaky,
+                cases.len(),
+                if num_flaky == 1 { "" } else { "s" },
+                self.runs.len(),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct CaseStats<'a> {
+    name: &'a str,
+    passed: usize,
+    runs: usize,
+    mean_ms: f64,
+    /// Population standard deviation.
+    stddev_ms: f64,
+    min_ms: u128,
+    max_ms: u128,
+    /// Whether the outputs (or the transcripts) are not the same in all of the runs.
+    outputs_differ: bool,
+    first_failure: Option<VerdictKind>,
+}
+
+impl<'a> CaseStats<'a> {
+    fn new(name: &'a str, verdicts: &[&Verdict]) -> Self {
+        let elapsed = verdicts
+            .iter()
+            .map(|v| v.elapsed.as_micros() as f64 / 1000.0)
+            .collect::<Vec<_>>();
+        let mean_ms = elapsed.iter().sum::<f64>() / elapsed.len() as f64;
+        let variance =
+            elapsed.iter().map(|t| (t - mean_ms).powi(2)).sum::<f64>() / elapsed.len() as f64;
+
+        Self {
+            name,
+            passed: verdicts
+                .iter()
+                .filter(|v| v.kind == VerdictKind::Accepted)
+                .count(),
+            runs: verdicts.len(),
+            mean_ms,
+            stddev_ms: variance.sqrt(),
+            min_ms: verdicts
+                .iter()
+                .map(|v| v.elapsed.as_millis())
+                .min()
+                .unwrap_or(0),
+            max_ms: verdicts
+                .iter()
+                .map(|v| v.elapsed.as_millis())
+                .max()
+                .unwrap_or(0),
+            outputs_differ: verdicts
+                .iter()
+                .filter(|v| v.kind != VerdictKind::TimelimitExceeded)
+                .map(|v| v.transcript.as_deref().unwrap_or(&v.stdout))
+                .collect::<HashSet<_>>()
+                .len()
+                > 1,
+            first_failure: verdicts
+                .iter()
+                .map(|v| v.kind)
+                .find(|&k| k != VerdictKind::Accepted),
+        }
+    }
+
+    /// Whether the case failed in any of the runs, or its output is unstable.
+    fn is_failure(&self) -> bool {
+        self.passed < self.runs || self.outputs_differ
+    }
+}
+
+fn write_report(
+    content: String,
+    report_path: Option<&Path>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    if let Some(report_path) = report_path {
+        if let Some(parent) = report_path.parent() {
+            crate::fs::create_dir_all(parent)?;
+        }
+        crate::fs::write(report_path, content + "\n")?;
+        shell.status("Wrote", report_path.display())?;
+    } else {
+        writeln!(shell.out(), "{}", content)?;
+        shell.out().flush()?;
+    }
+    Ok(())
+}
+
+fn xml_escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_owned(),
+            '<' => "&lt;".to_owned(),
+            '>' => "&gt;".to_owned(),
+            '"' => "&quot;".to_owned(),
+            '\'' => "&apos;".to_owned(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn format_bytes(n: u64) -> String {
+    if n >= 1024 * 1024 {
+        format!("{:.1} MiB", n as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KiB", n as f64 / 1024.0)
+    }
+}
+
+fn excerpt(s: &str, display_limit: usize) -> String {
+    if s.len() <= display_limit {
+        return s.to_owned();
+    }
+    let mut end = display_limit;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}<{} bytes omitted>", &s[..end], s.len() - end)
+}
+
+#[derive(Debug)]
+pub(crate) struct Verdict {
+    pub(crate) name: String,
+    pub(crate) kind: VerdictKind,
+    pub(crate) timelimit: Option<Duration>,
+    pub(crate) elapsed: Duration,
+    /// CPU time and peak memory. Only measured on Unix platforms.
+    pub(crate) usage: ResourceUsage,
+    pub(crate) stdin: String,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    pub(crate) expected: Option<String>,
+    /// Lines exchanged between the solution and the tester, for interactive problems.
+    pub(crate) transcript: Option<String>,
+    pub(crate) message: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VerdictKind {
+    Accepted,
+    WrongAnswer,
+    RuntimeError,
+    TimelimitExceeded,
+    MemoryLimitExceeded,
+    /// A sanitizer or Miri reported an error.
+    SanitizerError,
+}
+
+impl VerdictKind {
+    fn color(self) -> Color {
+        match self {
+            Self::Accepted => Color::Green,
+            Self::WrongAnswer | Self::RuntimeError => Color::Red,
+            Self::TimelimitExceeded | Self::MemoryLimitExceeded => Color::Yellow,
+            Self::SanitizerError => Color::Magenta,
+        }
+    }
+}
+
+impl fmt::Display for VerdictKind {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(match self {
+            Self::Accepted => "Accepted",
+            Self::WrongAnswer => "Wrong Answer",
+            Self::RuntimeError => "Runtime Error",
+            Self::TimelimitExceeded => "Timelimit Exceeded",
+            Self::MemoryLimitExceeded => "Memory Limit Exceeded",
+            Self::SanitizerError => "Sanitizer Error",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CaseStats, Verdict, VerdictKind};
+    use crate::testing::measure::ResourceUsage;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn case_stats() {
+        let verdict = |kind, elapsed_ms, stdout: &str| Verdict {
+            name: "sample1".to_owned(),
+            kind,
+            timelimit: None,
+            elapsed: Duration::from_millis(elapsed_ms),
+            usage: ResourceUsage::default(),
+            stdin: "".to_owned(),
+            stdout: stdout.to_owned(),
+            stderr: "".to_owned(),
+            expected: None,
+            transcript: None,
+            message: None,
+        };
+
+        let verdicts = vec![
+            verdict(VerdictKind::Accepted, 10, "1\n"),
+            verdict(VerdictKind::WrongAnswer, 20, "2\n"),
+            verdict(VerdictKind::Accepted, 30, "1\n"),
+        ];
+
+        let stats = CaseStats::new("sample1", &verdicts.iter().collect::<Vec<_>>());
+        assert_eq!(2, stats.passed);
+        assert_eq!(3, stats.runs);
+        assert!((stats.mean_ms - 20.0).abs() < 1e-9);
+        assert!((stats.stddev_ms - (200.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert_eq!((10, 30), (stats.min_ms, stats.max_ms));
+        assert!(stats.outputs_differ);
+        assert_eq!(Some(VerdictKind::WrongAnswer), stats.first_failure);
+        assert!(stats.is_failure());
+    }
+}
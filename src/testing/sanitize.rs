@@ -0,0 +1,173 @@
+use crate::{
+    process::{Present, ProcessBuilder},
+    shell::Shell,
+};
+use anyhow::Context as _;
+use cargo_metadata::{Metadata, Package};
+use maplit::btreemap;
+use snowchains_core::judge::CommandExpression;
+use std::{env, ffi::OsString, path::Path};
+use strum::{EnumString, EnumVariantNames, IntoStaticStr};
+
+/// Tool that detects undefined behavior the judges would not report.
+///
+/// All of them require a nightly toolchain.
+#[derive(EnumString, EnumVariantNames, IntoStaticStr, Clone, Copy, Debug, PartialEq, Eq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Sanitizer {
+    /// [AddressSanitizer](https://clang.llvm.org/docs/AddressSanitizer.html)
+    Address,
+    /// [MemorySanitizer](https://clang.llvm.org/docs/MemorySanitizer.html)
+    Memory,
+    /// [Miri](https://github.com/rust-lang/miri)
+    Miri,
+}
+
+impl Sanitizer {
+    /// Builds the `bin` for the sanitizer.
+    ///
+    /// Returns the command that runs the `bin` and the build command. For Miri, they are the same
+    /// `cargo miri run`.
+    pub(super) fn build(
+        self,
+        metadata: &Metadata,
+        package: &Package,
+        bin_name: &str,
+        shell: &mut Shell,
+    ) -> anyhow::Result<(CommandExpression, ProcessBuilder<Present>)> {
+        // Use the `cargo` proxy of rustup instead of `$CARGO` for `+nightly`.
+        let cargo = crate::process::which("cargo", &metadata.workspace_root)?;
+
+        if self == Self::Miri {
+            crate::process::process(&cargo)
+                .args(&["+nightly", "miri", "setup"])
+                .cwd(&metadata.workspace_root)
+                .exec_with_shell_status(shell)?;
+
+            // `cargo miri run` cannot build without running. The `bin` is compiled by the first
+            // run and reused by the rest, which [`batch::judge`] runs one by one.
+            //
+            // [`batch::judge`]: super::batch::judge
+            let args: Vec<OsString> = vec![
+                "+nightly".into(),
+                "miri".into(),
+                "run".into(),
+                "-q".into(),
+                "--bin".into(),
+                bin_name.into(),
+                "--manifest-path".into(),
+                package.manifest_path.clone().into(),
+            ];
+
+            let run = crate::process::process(&cargo)
+                .args(&args)
+                .cwd(&metadata.workspace_root);
+
+            let cmd = CommandExpression {
+                program: cargo.into(),
+                args,
+                cwd: metadata.workspace_root.clone(),
+                env: btreemap!(),
+            };
+            return Ok((cmd, run));
+        }
+
+        // `--target` is required so that build scripts and proc-macros are not instrumented.
+        let host = host_triple(&metadata.workspace_root, shell)?;
+
+        let target_dir = metadata
+            .target_directory
+            .join("cargo-compete")
+            .join("sanitize")
+            .join(<&str>::from(self));
+
+        let rustflags = match self {
+            Self::Address => "-Zsanitizer=address",
+            Self::Memory => "-Zsanitizer=memory -Zsanitizer-memory-track-origins",
+            Self::Miri => unreachable!(),
+        };
+
+        let build = crate::process::process(&cargo)
+            .env("RUSTFLAGS", rustflags)
+            .args(&["+nightly", "build", "--bin", bin_name])
+            .args(if self == Self::Memory {
+                &["-Zbuild-std"][..]
+            } else {
+                &[]
+            })
+            .arg("--target")
+            .arg(&host)
+            .arg("--manifest-path")
+            .arg(&package.manifest_path)
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .cwd(&metadata.workspace_root);
+        build.exec_with_shell_status(shell)?;
+
+        let artifact = target_dir
+            .join(&host)
+            .join("debug")
+            .join(bin_name)
+            .with_extension(env::consts::EXE_EXTENSION);
+
+        let cmd = CommandExpression {
+            program: artifact.into(),
+            args: vec![],
+            cwd: metadata.workspace_root.clone(),
+            env: btreemap!(),
+        };
+        Ok((cmd, build))
+    }
+
+    /// Finds the first line of a report in `stderr`.
+    pub(crate) fn find_report(self, stderr: &str) -> Option<&str> {
+        let markers: &[_] = match self {
+            Self::Address => &["ERROR: AddressSanitizer", "ERROR: LeakSanitizer"],
+            Self::Memory => &["WARNING: MemorySanitizer"],
+            Self::Miri => &["error: Undefined Behavior", "error: memory leaked"],
+        };
+        stderr
+            .lines()
+            .find(|line| markers.iter().any(|marker| line.contains(marker)))
+            .map(str::trim)
+    }
+}
+
+fn host_triple(cwd: &Path, shell: &mut Shell) -> anyhow::Result<String> {
+    let version = crate::process::with_which("rustc", cwd)?
+        .args(&["+nightly", "-vV"])
+        .read_with_shell_status(shell)?;
+
+    version
+        .lines()
+        .find(|line| line.starts_with("host: "))
+        .map(|line| line["host: ".len()..].trim().to_owned())
+        .with_context(|| "could not find the host triple in `rustc -vV`")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sanitizer;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn find_report() {
+        let stderr = r#"=================================================================
+==4321==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000014
+READ of size 4 at 0x602000000014 thread T0
+"#;
+        assert_eq!(
+            Some("==4321==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000014"),
+            Sanitizer::Address.find_report(stderr),
+        );
+        assert_eq!(None, Sanitizer::Memory.find_report(stderr));
+
+        let stderr = r#"error: Undefined Behavior: memory access failed: pointer must be in-bounds
+ --> src/bin/a.rs:5:20
+"#;
+        assert_eq!(
+            Some("error: Undefined Behavior: memory access failed: pointer must be in-bounds"),
+            Sanitizer::Miri.find_report(stderr),
+        );
+    }
+}
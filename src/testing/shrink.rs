@@ -0,0 +1,278 @@
+use crate::{
+    project::{CargoCompeteConfigTestMatch, PackageExt as _, PackageMetadataCargoCompeteBin},
+    shell::Shell,
+    testing::{
+        batch::{self, OutputJudge, RunOutput},
+        outcome::VerdictKind,
+    },
+};
+use anyhow::bail;
+use cargo_metadata::{Metadata, Package};
+use maplit::btreemap;
+use snowchains_core::judge::CommandExpression;
+use std::{path::Path, time::Duration};
+
+/// Timelimit for each run of `shrink` when the test suite does not have one. Reduced inputs are
+/// often invalid and may make the programs hang.
+const TIMELIMIT: Duration = Duration::from_secs(10);
+
+pub(crate) struct ShrinkArgs<'a> {
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) member: &'a Package,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) package_metadata_bin: &'a PackageMetadataCargoCompeteBin,
+    pub(crate) default_match: Option<CargoCompeteConfigTestMatch>,
+    pub(crate) input: &'a Path,
+    pub(crate) reference: &'a str,
+    pub(crate) name: Option<String>,
+    pub(crate) release: bool,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Minimizes a failing input and saves it to the test suite.
+pub(crate) fn shrink(args: ShrinkArgs<'_>) -> anyhow::Result<()> {
+    let ShrinkArgs {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        default_match,
+        input,
+        reference,
+        name,
+        release,
+        shell,
+    } = args;
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir_utf8(),
+        cargo_compete_config_test_suite,
+        &package_metadata_bin.problem,
+    )?;
+
+    let name = name.unwrap_or_else(|| {
+        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+        format!("{}-min", stem)
+    });
+    let input = crate::fs::read_to_string(input)?;
+
+    let bin = member.bin_target(&package_metadata_bin.name)?;
+    let target = CommandExpression {
+        program: super::cargo_build_bin(metadata, member, &bin.name, release, shell)?.into(),
+        args: vec![],
+        cwd: metadata.workspace_root.clone(),
+        env: btreemap!(),
+    };
+    let reference = super::workspace_bin_command(metadata, reference, release, shell)?;
+
+    let judge = OutputJudge::load(&test_suite_path, default_match, metadata, release, shell)?;
+    let timelimit = judge.timelimit.unwrap_or(TIMELIMIT);
+
+    if !test_suite_path.exists() {
+        judge.create_test_suite(&test_suite_path)?;
+    }
+
+    let input = minimize_and_save(
+        &input,
+        &reference,
+        &target,
+        &judge,
+        timelimit,
+        &test_suite_path,
+        default_match,
+        &name,
+        shell,
+    )?;
+
+    shell.status(
+        "Saved",
+        format!(
+            "`{}` ({} bytes) to {}",
+            name,
+            input.len(),
+            test_suite_path.display(),
+        ),
+    )?;
+    Ok(())
+}
+
+/// Minimizes `input`, then appends it with the output of `reference` to the test suite.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn minimize_and_save(
+    input: &str,
+    reference: &CommandExpression,
+    target: &CommandExpression,
+    judge: &OutputJudge,
+    timelimit: Duration,
+    test_suite_path: &Path,
+    default_match: Option<CargoCompeteConfigTestMatch>,
+    name: &str,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    shell.status("Shrinking", format!("the input ({} bytes)", input.len()))?;
+
+    let input = minimize_input(input, reference, target, judge, timelimit, |input| {
+        shell.status("Reduced", format!("to {} bytes", input.len()))?;
+        Ok(())
+    })?;
+
+    let expected = match batch::run(reference, &input, Some(timelimit))? {
+        RunOutput {
+            status: Some(status),
+            stdout,
+            ..
+        } if status.success() => stdout,
+        _ => bail!("the reference did not accept the minimized input"),
+    };
+
+    super::append_batch_test_case(
+        test_suite_path,
+        default_match,
+        name,
+        &input,
+        Some(&expected),
+    )?;
+    Ok(input)
+}
+
+/// Minimizes `input` while `target` still fails on it in the same way as on the original.
+///
+/// Lines are removed first, then tokens in the remaining lines. Inputs that make `reference` fail
+/// are regarded as invalid. The output of `target` is judged against the one of `reference` with
+/// `judge`, and the reduced input must result in the same verdict (e.g. `WrongAnswer`) as the
+/// original.
+///
+/// Fails if `target` passes on the original `input`.
+pub(super) fn minimize_input(
+    input: &str,
+    reference: &CommandExpression,
+    target: &CommandExpression,
+    judge: &OutputJudge,
+    timelimit: Duration,
+    mut on_progress: impl FnMut(&str) -> anyhow::Result<()>,
+) -> anyhow::Result<String> {
+    // `None` if `reference` rejects `input`.
+    let verdict = |input: &str| -> anyhow::Result<Option<VerdictKind>> {
+        let expected = match batch::run(reference, input, Some(timelimit))? {
+            RunOutput {
+                status: Some(status),
+                stdout,
+                ..
+            } if status.success() => stdout,
+            _ => return Ok(None),
+        };
+
+        let RunOutput { status, stdout, .. } = batch::run(target, input, Some(timelimit))?;
+
+        Ok(Some(match status {
+            None => VerdictKind::TimelimitExceeded,
+            Some(status) if !status.success() => VerdictKind::RuntimeError,
+            Some(_) => judge.judge(input, &stdout, &expected)?.0,
+        }))
+    };
+
+    let original = match verdict(input)? {
+        Some(VerdictKind::Accepted) => bail!("the target does not fail on the original input"),
+        Some(original) => original,
+        None => bail!("the reference rejects the original input"),
+    };
+
+    let fails = |input: &str| -> anyhow::Result<bool> { Ok(verdict(input)? == Some(original)) };
+
+    let mut fails = |input: &str| -> anyhow::Result<bool> {
+        let fails = fails(input)?;
+        if fails {
+            on_progress(input)?;
+        }
+        Ok(fails)
+    };
+
+    let lines = ddmin(input.lines().collect(), |lines| fails(&join_lines(lines)))?;
+
+    let tokens = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| line.split_whitespace().map(move |token| (i, token)))
+        .collect();
+
+    let tokens = ddmin(tokens, |tokens| fails(&join_tokens(tokens, lines.len())))?;
+
+    Ok(join_tokens(&tokens, lines.len()))
+}
+
+/// Simplified delta debugging, which only tries complements of the chunks.
+fn ddmin<T: Copy>(
+    mut items: Vec<T>,
+    mut fails: impl FnMut(&[T]) -> anyhow::Result<bool>,
+) -> anyhow::Result<Vec<T>> {
+    let mut n = 2;
+
+    while items.len() >= 2 {
+        let chunk_len = items.len().div_ceil(n);
+
+        let mut reduced = false;
+
+        for start in (0..items.len()).step_by(chunk_len) {
+            let end = usize::min(start + chunk_len, items.len());
+            let complement = items[..start]
+                .iter()
+                .chain(&items[end..])
+                .copied()
+                .collect::<Vec<_>>();
+
+            if fails(&complement)? {
+                items = complement;
+                n = usize::max(n - 1, 2);
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced {
+            if n >= items.len() {
+                break;
+            }
+            n = usize::min(n * 2, items.len());
+        }
+    }
+
+    Ok(items)
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+fn join_tokens(tokens: &[(usize, &str)], num_lines: usize) -> String {
+    let mut lines = vec![vec![]; num_lines];
+    for &(i, token) in tokens {
+        lines[i].push(token);
+    }
+    lines
+        .into_iter()
+        .map(|tokens| format!("{}\n", tokens.join(" ")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ddmin() -> anyhow::Result<()> {
+        let items = (0..100).collect::<Vec<_>>();
+        let minimized = super::ddmin(
+            items,
+            |items| Ok(items.contains(&13) && items.contains(&71)),
+        )?;
+        assert_eq!(vec![13, 71], minimized);
+        Ok(())
+    }
+
+    #[test]
+    fn join_tokens() {
+        let tokens = &[(0, "3"), (1, "1"), (1, "2"), (3, "4")];
+        assert_eq!("3\n1 2\n\n4\n", super::join_tokens(tokens, 4));
+    }
+}
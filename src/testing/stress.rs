@@ -0,0 +1,209 @@
+use crate::{
+    project::{CargoCompeteConfigTestMatch, PackageExt as _, PackageMetadataCargoCompeteBin},
+    shell::Shell,
+    testing::{
+        batch::{self, OutputJudge, RunOutput},
+        outcome::{TestOutcome, Verdict, VerdictKind},
+    },
+};
+use anyhow::bail;
+use az::SaturatingAs as _;
+use cargo_metadata::{Metadata, Package};
+use human_size::{Byte, Size};
+use maplit::btreemap;
+use snowchains_core::judge::CommandExpression;
+use std::time::Duration;
+
+/// Timelimit used when the test suite does not have one.
+const DEFAULT_TIMELIMIT: Duration = Duration::from_secs(2);
+
+pub(crate) struct StressArgs<'a> {
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) member: &'a Package,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) package_metadata_bin: &'a PackageMetadataCargoCompeteBin,
+    pub(crate) default_match: Option<CargoCompeteConfigTestMatch>,
+    pub(crate) generator: &'a str,
+    pub(crate) reference: &'a str,
+    pub(crate) iterations: Option<u64>,
+    pub(crate) name: Option<String>,
+    pub(crate) shrink: bool,
+    pub(crate) release: bool,
+    pub(crate) display_limit: Size,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Runs the generator, the reference solution, and the target repeatedly until their outputs
+/// differ.
+///
+/// The generator receives the iteration number as its only argument, so that it can be used as a
+/// seed.
+///
+/// The timelimit of the test suite (or [`DEFAULT_TIMELIMIT`]) is applied to all of the three
+/// programs. The target exceeding it is regarded as `TimelimitExceeded`. The outputs are judged
+/// with the `match` and the checker of the test suite.
+///
+/// The test suite file is created if it does not exist when a counterexample is found.
+pub(crate) fn stress(args: StressArgs<'_>) -> anyhow::Result<()> {
+    let StressArgs {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        default_match,
+        generator,
+        reference,
+        iterations,
+        name,
+        shrink,
+        release,
+        display_limit,
+        shell,
+    } = args;
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir_utf8(),
+        cargo_compete_config_test_suite,
+        &package_metadata_bin.problem,
+    )?;
+
+    let judge = OutputJudge::load(&test_suite_path, default_match, metadata, release, shell)?;
+    let timelimit = judge.timelimit.unwrap_or(DEFAULT_TIMELIMIT);
+
+    let bin = member.bin_target(&package_metadata_bin.name)?;
+    let target = CommandExpression {
+        program: super::cargo_build_bin(metadata, member, &bin.name, release, shell)?.into(),
+        args: vec![],
+        cwd: metadata.workspace_root.clone(),
+        env: btreemap!(),
+    };
+    let generator = super::workspace_bin_command(metadata, generator, release, shell)?;
+    let reference = super::workspace_bin_command(metadata, reference, release, shell)?;
+
+    shell.status(
+        "Running",
+        format!(
+            "{} iterations",
+            iterations.map_or_else(|| "unlimited".to_owned(), |n| n.to_string()),
+        ),
+    )?;
+
+    for seed in (1..).take_while(|&i| iterations.map_or(true, |n| i <= n)) {
+        let generator = CommandExpression {
+            program: generator.program.clone(),
+            args: vec![seed.to_string().into()],
+            cwd: generator.cwd.clone(),
+            env: generator.env.clone(),
+        };
+
+        let input = batch::run(&generator, "", Some(timelimit))?;
+        let input = expect_success(input, "the generator", timelimit)?;
+        let expected = batch::run(&reference, &input, Some(timelimit))?;
+        let expected = expect_success(expected, "the reference", timelimit)?;
+
+        let RunOutput {
+            status,
+            stdout,
+            stderr,
+            elapsed,
+            usage,
+        } = batch::run(&target, &input, Some(timelimit))?;
+
+        let (kind, message) = match status {
+            None => (VerdictKind::TimelimitExceeded, None),
+            Some(status) if !status.success() => (
+                VerdictKind::RuntimeError,
+                Some(format!("the solution exited with {}", status)),
+            ),
+            Some(_) => judge.judge(&input, &stdout, &expected)?,
+        };
+
+        if kind == VerdictKind::Accepted {
+            if seed % 100 == 0 {
+                shell.status("Passed", format!("{} iterations", seed))?;
+            }
+            continue;
+        }
+
+        let name = name.unwrap_or_else(|| format!("stress-{}", seed));
+
+        let outcome = TestOutcome {
+            verdicts: vec![Verdict {
+                name: name.clone(),
+                kind,
+                timelimit: Some(timelimit),
+                elapsed,
+                usage,
+                stdin: input.clone(),
+                stdout,
+                stderr,
+                expected: Some(expected.clone()),
+                transcript: None,
+                message,
+            }],
+            memory_limit: None,
+        };
+
+        let display_limit = display_limit.into::<Byte>().value().saturating_as();
+
+        writeln!(shell.err())?;
+        outcome.print_pretty(shell.err(), Some(display_limit))?;
+        writeln!(shell.err())?;
+
+        if !test_suite_path.exists() {
+            judge.create_test_suite(&test_suite_path)?;
+        }
+
+        let input = if shrink {
+            super::shrink::minimize_and_save(
+                &input,
+                &reference,
+                &target,
+                &judge,
+                timelimit,
+                &test_suite_path,
+                default_match,
+                &name,
+                shell,
+            )?
+        } else {
+            super::append_batch_test_case(
+                &test_suite_path,
+                default_match,
+                &name,
+                &input,
+                Some(&expected),
+            )?;
+            input
+        };
+
+        shell.status(
+            "Saved",
+            format!(
+                "`{}` ({} bytes) to {}",
+                name,
+                input.len(),
+                test_suite_path.display(),
+            ),
+        )?;
+
+        return outcome.error_on_fail();
+    }
+
+    shell.status("Finished", "no counterexample found")?;
+    Ok(())
+}
+
+fn expect_success(output: RunOutput, what: &str, timelimit: Duration) -> anyhow::Result<String> {
+    match output.status {
+        Some(status) if status.success() => Ok(output.stdout),
+        Some(status) => bail!(
+            "{} exited with {}: {}",
+            what,
+            status,
+            output.stderr.trim_end(),
+        ),
+        None => bail!("{} did not finish within {:?}", what, timelimit),
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{project::PackageExt as _, testing::Args};
+use cargo_metadata::{Metadata, Package, PackageId};
+use ignore::WalkBuilder;
+use snowchains_core::testsuite::{Additional, BatchTestSuite, TestSuite};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+use termcolor::Color;
+
+/// Runs [`super::test`] every time the source files or the test suite are modified, until Ctrl-C
+/// is pressed.
+pub(crate) fn watch(args: Args<'_>) -> anyhow::Result<()> {
+    let Args {
+        metadata,
+        member,
+        cargo_compete_config_test_suite,
+        package_metadata_bin,
+        default_match,
+        release,
+        test_case_names,
+        display_limit,
+        memory_limit,
+        no_memory_limit,
+        as_submitted,
+        submit_via_binary,
+        sanitizer,
+        repeat,
+        format,
+        report_path,
+        shell,
+    } = args;
+
+    let _ctrl_c = super::ctrl_c::listen();
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir_utf8(),
+        cargo_compete_config_test_suite,
+        &package_metadata_bin.problem,
+    )?;
+
+    loop {
+        shell.clear_screen()?;
+
+        let result = super::test(Args {
+            metadata,
+            member,
+            cargo_compete_config_test_suite,
+            package_metadata_bin,
+            default_match,
+            release,
+            test_case_names: test_case_names.clone(),
+            display_limit,
+            memory_limit,
+            no_memory_limit,
+            as_submitted,
+            submit_via_binary,
+            sanitizer,
+            repeat,
+            format,
+            report_path,
+            shell: &mut *shell,
+        });
+
+        if let Err(err) = result {
+            shell.status_with_color("Failed", err, Color::Red)?;
+        }
+
+        if super::ctrl_c::interrupted() {
+            break;
+        }
+
+        let paths = watched_paths(
+            metadata,
+            member,
+            &package_metadata_bin.name,
+            &test_suite_path,
+        );
+        shell.status(
+            "Watching",
+            format!("{} paths. press Ctrl-C to stop", paths.len()),
+        )?;
+
+        let before = snapshot(&paths);
+        loop {
+            thread::sleep(Duration::from_millis(300));
+            if super::ctrl_c::interrupted() {
+                shell.status("Stopped", "watching")?;
+                return Ok(());
+            }
+            if snapshot(&paths) != before {
+                break;
+            }
+        }
+    }
+
+    shell.status("Stopped", "watching")?;
+    Ok(())
+}
+
+fn watched_paths(
+    metadata: &Metadata,
+    member: &Package,
+    bin_name: &str,
+    test_suite_path: &Path,
+) -> Vec<PathBuf> {
+    let mut paths = vec![member.manifest_path.clone(), test_suite_path.to_owned()];
+
+    if let Ok(bin) = member.bin_target(bin_name) {
+        paths.push(bin.src_path.clone());
+    }
+
+    for package in local_dependencies(metadata, &member.id) {
+        paths.push(package.manifest_dir().to_owned());
+    }
+
+    if let Ok((TestSuite::Batch(BatchTestSuite { extend, .. }), _)) =
+        super::read_test_suite(test_suite_path)
+    {
+        for additional in extend {
+            match additional {
+                Additional::Text { path, .. } => {
+                    paths.push(test_suite_path.parent().unwrap().join(path));
+                }
+            }
+        }
+    }
+
+    paths
+}
+
+/// Path dependencies of `root`, including indirect ones.
+fn local_dependencies<'a>(metadata: &'a Metadata, root: &PackageId) -> Vec<&'a Package> {
+    let nodes = match &metadata.resolve {
+        Some(resolve) => &resolve.nodes,
+        None => return vec![],
+    };
+
+    let mut visited = HashSet::new();
+    let mut stack = vec![root];
+    let mut acc = vec![];
+
+    while let Some(id) = stack.pop() {
+        if let Some(node) = nodes.iter().find(|n| n.id == *id) {
+            for dep in &node.dependencies {
+                if visited.insert(dep) && metadata[dep].source.is_none() {
+                    acc.push(&metadata[dep]);
+                    stack.push(dep);
+                }
+            }
+        }
+    }
+    acc
+}
+
+fn snapshot(paths: &[PathBuf]) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    let mut acc = BTreeMap::new();
+
+    for path in paths {
+        if path.is_dir() {
+            for entry in WalkBuilder::new(path).build().flatten() {
+                if entry.path().is_file() {
+                    acc.insert(entry.path().to_owned(), modified(entry.path()));
+                }
+            }
+        } else {
+            acc.insert(path.clone(), modified(path));
+        }
+    }
+
+    return acc;
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        path.metadata().and_then(|m| m.modified()).ok()
+    }
+}
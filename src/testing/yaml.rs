@@ -0,0 +1,348 @@
+//! Textual edits of test suite files that keep the comments and the formatting the user wrote.
+
+/// Inserts a case at the end of the top-level `cases` sequence.
+///
+/// Returns `None` if `yaml` is not in a shape this function understands. The caller should
+/// parse the result and fall back to re-serializing the whole file in that case.
+pub(super) fn append_case(
+    yaml: &str,
+    name: &str,
+    input: &str,
+    output: Option<&str>,
+) -> Option<String> {
+    let lines = yaml.lines().collect::<Vec<_>>();
+
+    let start = lines.iter().position(|l| l.starts_with("cases:"))?;
+    let rest = lines[start]["cases:".len()..].trim();
+
+    let (mut acc, indent, end) = match rest {
+        "" => {
+            let end = value_end(&lines, start);
+
+            let indent = lines[start + 1..end]
+                .iter()
+                .find(|l| l.trim_start().starts_with('-'))
+                .map(|l| indent_of(l))
+                .unwrap_or("  ");
+
+            (lines[..end].to_vec(), indent.to_owned(), end)
+        }
+        "[]" => {
+            let mut acc = lines[..start].to_vec();
+            acc.push("cases:");
+            (acc, "  ".to_owned(), start + 1)
+        }
+        _ => return None,
+    };
+
+    let entry = case_entry(&indent, name, input, output);
+    acc.extend(entry.lines());
+    acc.extend(&lines[end..]);
+
+    let mut acc = acc.join("\n");
+    if yaml.ends_with('\n') {
+        acc += "\n";
+    }
+    Some(acc)
+}
+
+/// Sets `out` of the `index`-th case in the top-level `cases` sequence, replacing the existing one.
+///
+/// Returns `None` if `yaml` is not in a shape this function understands, like [`append_case`].
+pub(super) fn set_output(yaml: &str, index: usize, output: &str) -> Option<String> {
+    let lines = yaml.lines().collect::<Vec<_>>();
+
+    let start = lines.iter().position(|l| l.starts_with("cases:"))?;
+    if !lines[start]["cases:".len()..].trim().is_empty() {
+        return None;
+    }
+    let end = value_end(&lines, start);
+
+    let indent = lines[start + 1..end]
+        .iter()
+        .find(|l| l.trim_start().starts_with('-'))
+        .map(|l| indent_of(l))?;
+    let items = (start + 1..end)
+        .filter(|&i| indent_of(lines[i]) == indent && lines[i][indent.len()..].starts_with("- "))
+        .collect::<Vec<_>>();
+
+    let item_start = *items.get(index)?;
+    let item_end = items.get(index + 1).copied().unwrap_or(end);
+
+    // Flow mappings such as `- { in: "", out: "" }` are not supported.
+    if lines[item_start][indent.len() + 2..].starts_with('{') {
+        return None;
+    }
+
+    // Keys of the item are at this column, including the first one after `- `.
+    let column = indent.len() + 2;
+    let is_key = |i: usize, key: &str| {
+        (i == item_start || indent_of(lines[i]).len() == column)
+            && lines[i][column..].starts_with(key)
+    };
+
+    let (prefix, replaced) = match (item_start..item_end).find(|&i| is_key(i, "out:")) {
+        Some(i) => {
+            // Block scalars continue while the lines are indented deeper than the key.
+            let value_end = (i + 1..item_end)
+                .find(|&j| !lines[j].trim().is_empty() && indent_of(lines[j]).len() <= column)
+                .unwrap_or(item_end);
+            let value_end = (i + 1..value_end)
+                .rev()
+                .find(|&j| !lines[j].trim().is_empty())
+                .map_or(i + 1, |j| j + 1);
+            let prefix = if i == item_start { "- " } else { "  " };
+            (prefix, i..value_end)
+        }
+        None => {
+            let last = (item_start..item_end)
+                .rev()
+                .find(|&i| i == item_start || indent_of(lines[i]).len() > indent.len())
+                .unwrap();
+            ("  ", last + 1..last + 1)
+        }
+    };
+
+    let entry = format!("{}{}out: {}", indent, prefix, scalar(indent, output));
+
+    let mut acc = lines[..replaced.start].to_vec();
+    acc.extend(entry.lines());
+    acc.extend(&lines[replaced.end..]);
+
+    let mut acc = acc.join("\n");
+    if yaml.ends_with('\n') {
+        acc += "\n";
+    }
+    Some(acc)
+}
+
+/// Replaces the top-level `pending` with `pending`, removing the key if it is empty.
+///
+/// Returns `None` if there is no `pending` to replace.
+pub(super) fn set_pending(yaml: &str, pending: &[String]) -> Option<String> {
+    let lines = yaml.lines().collect::<Vec<_>>();
+
+    let start = lines.iter().position(|l| l.starts_with("pending:"))?;
+    let end = value_end(&lines, start);
+
+    let value = format!(
+        "pending: [{}]",
+        pending
+            .iter()
+            .map(|s| quote(s))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+
+    let mut acc = lines[..start].to_vec();
+    if !pending.is_empty() {
+        acc.push(&value);
+    }
+    acc.extend(&lines[end..]);
+
+    let mut acc = acc.join("\n");
+    if yaml.ends_with('\n') && !acc.is_empty() {
+        acc += "\n";
+    }
+    Some(acc)
+}
+
+/// End of the value of the top-level key at `start`, excluding the blank lines before the next
+/// key.
+fn value_end(lines: &[&str], start: usize) -> usize {
+    let end = (start + 1..lines.len())
+        .find(|&i| {
+            let line = lines[i];
+            !line.is_empty() && !line.starts_with(&[' ', '-', '#'][..])
+        })
+        .unwrap_or(lines.len());
+
+    (start + 1..end)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+        .map_or(start + 1, |i| i + 1)
+}
+
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn case_entry(indent: &str, name: &str, input: &str, output: Option<&str>) -> String {
+    let mut entry = format!("{}- name: {}\n", indent, quote(name));
+    entry += &format!("{}  in: {}\n", indent, scalar(indent, input));
+    if let Some(output) = output {
+        entry += &format!("{}  out: {}\n", indent, scalar(indent, output));
+    }
+    entry
+}
+
+/// Formats `s` as a literal block scalar if possible, or as a double-quoted one otherwise.
+fn scalar(indent: &str, s: &str) -> String {
+    let needs_quote = s.is_empty()
+        || s.starts_with(&[' ', '\t', '\n'][..])
+        || s.ends_with("\n\n")
+        || s.chars().any(|c| c.is_control() && c != '\n');
+
+    if needs_quote {
+        return quote(s);
+    }
+
+    let mut acc = format!("|{}\n", if s.ends_with('\n') { "" } else { "-" });
+    for line in s.lines() {
+        if !line.is_empty() {
+            acc += indent;
+            acc += "    ";
+            acc += line;
+        }
+        acc += "\n";
+    }
+    acc.trim_end_matches('\n').to_owned()
+}
+
+fn quote(s: &str) -> String {
+    // JSON strings are valid YAML double-quoted scalars.
+    serde_json::to_string(s).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn append_case() {
+        let yaml = r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases:
+  # from the problem page
+  - name: sample1
+    in: |
+      1 2
+    out: |
+      3
+
+extend: []
+"#;
+
+        assert_eq!(
+            Some(
+                r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases:
+  # from the problem page
+  - name: sample1
+    in: |
+      1 2
+    out: |
+      3
+  - name: "edge"
+    in: |
+      0 0
+    out: |-
+      0
+
+extend: []
+"#
+                .to_owned()
+            ),
+            super::append_case(yaml, "edge", "0 0\n", Some("0")),
+        );
+
+        assert_eq!(
+            Some("cases:\n  - name: \"a\"\n    in: \" 1\\n\"\n".to_owned()),
+            super::append_case("cases: []\n", "a", " 1\n", None),
+        );
+
+        assert_eq!(
+            None,
+            super::append_case("type: Interactive\n", "a", "", None)
+        );
+    }
+
+    #[test]
+    fn set_output() {
+        let yaml = r#"cases:
+  - name: sample1
+    in: |
+      1 2
+    out: |
+      4
+    # TODO
+  - name: sample2
+    in: |
+      3 4
+
+pending: ["sample1"]
+"#;
+
+        assert_eq!(
+            Some(
+                r#"cases:
+  - name: sample1
+    in: |
+      1 2
+    out: |
+      3
+    # TODO
+  - name: sample2
+    in: |
+      3 4
+
+pending: ["sample1"]
+"#
+                .to_owned()
+            ),
+            super::set_output(yaml, 0, "3\n"),
+        );
+
+        assert_eq!(
+            Some(
+                r#"cases:
+  - name: sample1
+    in: |
+      1 2
+    out: |
+      4
+    # TODO
+  - name: sample2
+    in: |
+      3 4
+    out: |
+      7
+
+pending: ["sample1"]
+"#
+                .to_owned()
+            ),
+            super::set_output(yaml, 1, "7\n"),
+        );
+
+        assert_eq!(
+            Some("cases:\n  - out: \"\"\n    in: \"\"\n".to_owned()),
+            super::set_output("cases:\n  - out: ~\n    in: \"\"\n", 0, ""),
+        );
+
+        assert_eq!(None, super::set_output(yaml, 2, ""));
+        assert_eq!(None, super::set_output("cases:\n  - { in: \"\" }\n", 0, ""));
+    }
+
+    #[test]
+    fn set_pending() {
+        let yaml = "cases: []\npending:\n  - a\n  - b\n\nextend: []\n";
+
+        assert_eq!(
+            Some("cases: []\npending: [\"b\"]\n\nextend: []\n".to_owned()),
+            super::set_pending(yaml, &["b".to_owned()]),
+        );
+        assert_eq!(
+            Some("cases: []\n\nextend: []\n".to_owned()),
+            super::set_pending(yaml, &[]),
+        );
+        assert_eq!(None, super::set_pending("cases: []\n", &[]));
+    }
+}
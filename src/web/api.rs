@@ -0,0 +1,90 @@
+//! Clients of the public APIs of Codeforces and yukicoder, for what `snowchains_core` does not
+//! provide.
+
+use anyhow::{bail, Context as _};
+use itertools::Itertools as _;
+use rand::Rng as _;
+use serde::{de::DeserializeOwned, Deserialize};
+use sha2::{Digest as _, Sha512};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// Calls Codeforces API with the authorization.
+///
+/// <https://codeforces.com/apiHelp>
+pub(crate) fn codeforces_api<T: DeserializeOwned>(
+    method: &str,
+    params: &[(&str, &str)],
+    api_key: &str,
+    api_secret: &str,
+) -> anyhow::Result<T> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)?
+        .as_secs()
+        .to_string();
+
+    let mut params = params
+        .iter()
+        .copied()
+        .chain(vec![("apiKey", api_key), ("time", &*time)])
+        .collect::<Vec<_>>();
+    params.sort();
+
+    let query = params.iter().map(|(k, v)| format!("{}={}", k, v)).join("&");
+    let rand = format!("{:06}", rand::thread_rng().gen_range(0, 1_000_000));
+    let api_sig = hex::encode(Sha512::digest(
+        format!("{}/{}?{}#{}", rand, method, query, api_secret).as_bytes(),
+    ));
+
+    let mut url = format!("https://codeforces.com/api/{}", method)
+        .parse::<Url>()
+        .unwrap();
+    url.query_pairs_mut()
+        .extend_pairs(&params)
+        .append_pair("apiSig", &format!("{}{}", rand, api_sig));
+
+    #[derive(Deserialize)]
+    struct Response<T> {
+        status: String,
+        comment: Option<String>,
+        result: Option<T>,
+    }
+
+    let Response {
+        status,
+        comment,
+        result,
+    } = serde_json::from_str(&get(url, None)?)
+        .with_context(|| "could not parse the response from Codeforces")?;
+
+    match result {
+        Some(result) if status == "OK" => Ok(result),
+        _ => bail!(
+            "Codeforces API returned `{}`: {}",
+            status,
+            comment.unwrap_or_default(),
+        ),
+    }
+}
+
+/// Calls yukicoder API with the API key.
+///
+/// <https://petstore.swagger.io/?url=https://yukicoder.me/api/swagger.yaml>
+pub(crate) fn yukicoder_api<T: DeserializeOwned>(path: &str, api_key: &str) -> anyhow::Result<T> {
+    let url = format!("https://yukicoder.me/api/v1/{}", path)
+        .parse::<Url>()
+        .unwrap();
+    serde_json::from_str(&get(url, Some(api_key))?)
+        .with_context(|| "could not parse the response from yukicoder")
+}
+
+fn get(url: Url, bearer: Option<&str>) -> anyhow::Result<String> {
+    let mut req = reqwest::blocking::Client::builder()
+        .timeout(crate::web::TIMEOUT)
+        .build()?
+        .get(url);
+    if let Some(bearer) = bearer {
+        req = req.bearer_auth(bearer);
+    }
+    req.send()?.error_for_status()?.text().map_err(Into::into)
+}
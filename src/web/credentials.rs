@@ -71,6 +71,19 @@ pub(crate) fn codeforces_api_key_and_secret(shell: &mut Shell) -> anyhow::Result
     }
 }
 
+/// Handle of the user, which Codeforces API requires to find the user's submissions.
+pub(crate) fn codeforces_handle(shell: &mut Shell) -> anyhow::Result<String> {
+    let path = token_path("codeforces-handle.json")?;
+    if path.exists() {
+        crate::fs::read_json(path)
+    } else {
+        let handle = shell.read_reply("Codeforces handle: ")?.trim().to_owned();
+        crate::fs::create_dir_all(path.parent().unwrap())?;
+        crate::fs::write_json(path, &handle)?;
+        Ok(handle)
+    }
+}
+
 fn token_path(file_name: &str) -> anyhow::Result<PathBuf> {
     let data_local_dir =
         dirs::data_local_dir().with_context(|| "could not find the local data directory")?;
@@ -0,0 +1,147 @@
+use crate::{shell::Shell, web::credentials};
+use anyhow::{bail, Context as _};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use snowchains_core::web::{
+    Atcoder, AtcoderRetrieveLanguagesCredentials, AtcoderRetrieveLanguagesTarget, Codeforces,
+    CodeforcesRetrieveLanguagesCredentials, CodeforcesRetrieveLanguagesTarget, CookieStorage,
+    PlatformKind, RetrieveLanguages, RetrieveLanguagesOutcome,
+};
+use std::{
+    borrow::BorrowMut as _,
+    cell::RefCell,
+    path::{Path, PathBuf},
+};
+
+/// Retrieves the languages in the submit form of the platform, as `ID => name`.
+///
+/// `contest` is used to find the submit form. It is required for Codeforces. For AtCoder, it is
+/// used together with `problem`, and the form of the practice contest is used without them.
+pub(crate) fn retrieve(
+    platform: PlatformKind,
+    contest: Option<&str>,
+    problem: Option<&str>,
+    cookies_path: &Path,
+    shell: &mut Shell,
+) -> anyhow::Result<IndexMap<String, String>> {
+    let timeout = crate::web::TIMEOUT;
+
+    let RetrieveLanguagesOutcome { names_by_id } = match platform {
+        PlatformKind::Atcoder => {
+            let contest_and_problem = match (contest, problem) {
+                (Some(contest), Some(problem)) => Some((contest.to_owned(), problem.to_owned())),
+                (None, None) => None,
+                _ => bail!("`--contest` and `--problem` must be given together for AtCoder"),
+            };
+
+            let shell = RefCell::new(shell.borrow_mut());
+
+            let credentials = AtcoderRetrieveLanguagesCredentials {
+                username_and_password: &mut credentials::username_and_password(
+                    &shell,
+                    "Username: ",
+                    "Password: ",
+                ),
+            };
+
+            Atcoder::exec(RetrieveLanguages {
+                target: AtcoderRetrieveLanguagesTarget {
+                    contest_and_problem,
+                },
+                credentials,
+                cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
+                timeout,
+                shell: &shell,
+            })?
+        }
+        PlatformKind::Codeforces => {
+            let contest = contest
+                .with_context(|| "`--contest` is required for Codeforces")?
+                .to_owned();
+
+            let shell = RefCell::new(shell.borrow_mut());
+
+            let credentials = CodeforcesRetrieveLanguagesCredentials {
+                username_and_password: &mut credentials::username_and_password(
+                    &shell,
+                    "Handle/Email: ",
+                    "Password: ",
+                ),
+            };
+
+            Codeforces::exec(RetrieveLanguages {
+                target: CodeforcesRetrieveLanguagesTarget { contest },
+                credentials,
+                cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
+                timeout,
+                shell: &shell,
+            })?
+        }
+        PlatformKind::Yukicoder => RetrieveLanguagesOutcome {
+            names_by_id: retrieve_yukicoder_languages()?,
+        },
+    };
+
+    Ok(names_by_id)
+}
+
+/// yukicoder provides the list with the API, without authentication.
+fn retrieve_yukicoder_languages() -> anyhow::Result<IndexMap<String, String>> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Language {
+        id: String,
+        name: String,
+        ver: String,
+    }
+
+    let json = reqwest::blocking::Client::builder()
+        .timeout(crate::web::TIMEOUT)
+        .build()?
+        .get("https://yukicoder.me/api/v1/languages")
+        .send()?
+        .error_for_status()?
+        .text()?;
+
+    let languages = serde_json::from_str::<Vec<Language>>(&json)
+        .with_context(|| "could not parse the response from yukicoder")?;
+
+    Ok(languages
+        .into_iter()
+        .map(|Language { id, name, ver }| (id, format!("{} ({})", name, ver)))
+        .collect())
+}
+
+pub(crate) fn read_cache(platform: PlatformKind) -> anyhow::Result<IndexMap<String, String>> {
+    let path = cache_path(platform)?;
+    if !path.exists() {
+        bail!(
+            "`{}` does not exist. run `cargo compete languages {}` first",
+            path.display(),
+            platform.to_kebab_case_str(),
+        );
+    }
+    serde_json::from_str(&crate::fs::read_to_string(&path)?)
+        .with_context(|| format!("could not parse `{}`", path.display()))
+}
+
+pub(crate) fn write_cache(
+    platform: PlatformKind,
+    names_by_id: &IndexMap<String, String>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let path = cache_path(platform)?;
+    crate::fs::create_dir_all(path.parent().unwrap())?;
+    crate::fs::write(&path, serde_json::to_string_pretty(names_by_id)?)?;
+    shell.status("Wrote", path.display())?;
+    Ok(())
+}
+
+fn cache_path(platform: PlatformKind) -> anyhow::Result<PathBuf> {
+    let cache_dir = dirs::cache_dir().with_context(|| "could not find the cache directory")?;
+    Ok(cache_dir
+        .join("cargo-compete")
+        .join("languages")
+        .join(platform.to_kebab_case_str())
+        .with_extension("json"))
+}
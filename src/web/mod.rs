@@ -1,5 +1,11 @@
+pub(crate) mod api;
 pub(crate) mod credentials;
+pub(crate) mod languages;
+pub(crate) mod retrieve_submission;
 pub(crate) mod retrieve_testcases;
+pub(crate) mod session;
+pub(crate) mod submission_summaries;
+pub(crate) mod watch_submissions;
 
 use std::time::Duration;
 
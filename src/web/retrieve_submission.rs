@@ -0,0 +1,78 @@
+use crate::{shell::Shell, web::session::Session};
+use anyhow::{bail, Context as _};
+use scraper::{Html, Selector};
+use std::path::Path;
+use url::Url;
+
+/// Retrieves the code of the submission at `url`.
+///
+/// The pages are retrieved with the cookies saved by `login`, logging in if required, so that the
+/// submissions in running contests can be retrieved.
+pub(crate) fn retrieve_code(
+    url: &Url,
+    cookies_path: &Path,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    let host = url.host_str().unwrap_or_default();
+
+    let (url, extract): (_, fn(&str) -> Option<String>) = match host {
+        "atcoder.jp" => (url.clone(), |html| find_code(html, "#submission-code")),
+        "codeforces.com" => (url.clone(), |html| find_code(html, "#program-source-text")),
+        // The raw code is served at `/submissions/{id}/source`.
+        "yukicoder.me" => {
+            let id = crate::web::watch_submissions::parse_yukicoder_submission_id(url.as_str())?;
+            let url = format!("https://yukicoder.me/submissions/{}/source", id).parse()?;
+            (url, |text| Some(text.to_owned()))
+        }
+        _ => bail!("unsupported URL: {}", url),
+    };
+
+    let text = Session::new(cookies_path)?.get_text(&url, shell)?;
+
+    extract(&text).with_context(|| format!("could not find the code in {}", url))
+}
+
+/// Finds the latest submission for `task_url` in the submission summaries of `contest`.
+pub(crate) fn latest_atcoder_submission(
+    contest: &str,
+    task_url: &Url,
+    cookies_path: &Path,
+    shell: &mut Shell,
+) -> anyhow::Result<Url> {
+    // The summaries are in descending order of the submission time.
+    crate::web::submission_summaries::retrieve_atcoder(contest, cookies_path, shell)?
+        .summaries
+        .into_iter()
+        .find(|s| s.task.url == *task_url)
+        .map(|s| s.detail)
+        .with_context(|| format!("could not find any submission for {}", task_url))
+}
+
+/// Finds the text of the element that matches `selector`.
+fn find_code(html: &str, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).unwrap();
+    Html::parse_document(html)
+        .select(&selector)
+        .next()
+        .map(|pre| pre.text().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn find_code() {
+        let html = r#"<div>
+<pre id="submission-code" class="prettyprint linenums">fn main() {
+    println!(&quot;{}&quot;, 1 &lt; 2 &amp;&amp; true);
+}
+</pre>
+</div>"#;
+        assert_eq!(
+            Some("fn main() {\n    println!(\"{}\", 1 < 2 && true);\n}\n".to_owned()),
+            super::find_code(html, "#submission-code"),
+        );
+        assert_eq!(None, super::find_code(html, "#program-source-text"));
+    }
+}
@@ -1,17 +1,20 @@
 use crate::{
     project::{
-        PackageExt as _, PackageMetadataCargoCompeteBin, TargetProblem, TargetProblemYukicoder,
+        CargoCompeteConfigTestMatch, PackageExt as _, PackageMetadataCargoCompeteBin,
+        TargetProblem, TargetProblemYukicoder,
     },
     shell::Shell,
-    web::credentials,
+    testing::TestSuiteExt,
+    web::{credentials, session::Session},
 };
 use cargo_metadata::Package;
 use heck::KebabCase as _;
 use indexmap::IndexMap;
 use liquid::object;
 use maplit::btreemap;
+use scraper::{Html, Selector};
 use snowchains_core::{
-    testsuite::{Additional, BatchTestSuite, TestSuite},
+    testsuite::{Additional, BatchTestSuite, Match, TestSuite},
     web::{
         Atcoder, AtcoderRetrieveFullTestCasesCredentials,
         AtcoderRetrieveSampleTestCasesCredentials, AtcoderRetrieveTestCasesTargets, Codeforces,
@@ -35,6 +38,7 @@ pub(crate) fn dl_for_existing_package(
     package_metadata_bin: &mut IndexMap<String, PackageMetadataCargoCompeteBin>,
     bin_indexes: Option<&HashSet<String>>,
     full: bool,
+    problem_pages: bool,
     workspace_root: &Path,
     test_suite_path: &liquid::Template,
     cookies_path: &Path,
@@ -121,6 +125,8 @@ pub(crate) fn dl_for_existing_package(
             package.manifest_dir_utf8(),
             test_suite_path,
             outcome,
+            problem_pages,
+            cookies_path,
             shell,
         )?;
     }
@@ -256,15 +262,23 @@ pub(crate) fn dl_from_yukicoder(
     })
 }
 
+/// Saves the test suites in `outcome`.
+///
+/// If `problem_pages` is `true`, the problem pages are retrieved to find the memory limits and the
+/// floating-point tolerances.
 pub(crate) fn save_test_cases(
     workspace_root: &Path,
     pkg_manifest_dir: &str,
     path: &liquid::Template,
     outcome: RetrieveTestCasesOutcome,
+    problem_pages: bool,
+    cookies_path: &Path,
     shell: &mut Shell,
 ) -> anyhow::Result<Vec<PathBuf>> {
     let mut acc = vec![];
 
+    let mut session = Session::new(cookies_path)?;
+
     let contest = outcome
         .contest
         .as_ref()
@@ -273,6 +287,7 @@ pub(crate) fn save_test_cases(
 
     for snowchains_core::web::RetrieveTestCasesOutcomeProblem {
         index,
+        url,
         mut test_suite,
         text_files,
         ..
@@ -320,8 +335,56 @@ pub(crate) fn save_test_cases(
             }
         }
 
+        // Keep `tester`, `checker` and so on that the user has written, and `match` unless it is
+        // the default one.
+        let (existing_match, mut test_suite_ext) = if path.exists() {
+            crate::testing::read_test_suite(&path)
+                .map(|(existing, ext)| match existing {
+                    TestSuite::Batch(BatchTestSuite { r#match, .. }) if r#match != Match::Lines => {
+                        (Some(r#match), ext)
+                    }
+                    _ => (None, ext),
+                })
+                .unwrap_or_default()
+        } else {
+            (None, TestSuiteExt::default())
+        };
+
+        if let (TestSuite::Batch(BatchTestSuite { r#match, .. }), Some(existing_match)) =
+            (&mut test_suite, existing_match)
+        {
+            *r#match = existing_match;
+        }
+
+        match problem_pages.then(|| session.get_text(&url, shell)) {
+            Some(Ok(html)) => {
+                match find_memory_limit(&html) {
+                    Some(memorylimit) => test_suite_ext.memorylimit = Some(memorylimit),
+                    None => shell.warn(format!("could not find the memory limit in {}", url))?,
+                }
+
+                // Only when neither the site nor the user has set `match`. A checker decides the
+                // verdicts by itself.
+                if let TestSuite::Batch(BatchTestSuite { r#match, .. }) = &mut test_suite {
+                    if *r#match == Match::Lines && test_suite_ext.checker.is_none() {
+                        if let Some(tolerance) = find_tolerance(&html) {
+                            *r#match = serde_yaml::from_value(tolerance.to_yaml())?;
+                            shell.status(
+                                "Detected",
+                                format!("floating-point tolerance in {}", url),
+                            )?;
+                        }
+                    }
+                }
+            }
+            Some(Err(err)) => {
+                shell.warn(format!("could not retrieve the problem page: {}", err))?
+            }
+            None => {}
+        }
+
         crate::fs::create_dir_all(path.parent().unwrap())?;
-        crate::fs::write(&path, test_suite.to_yaml_pretty())?;
+        crate::testing::write_test_suite(&path, &test_suite, &test_suite_ext)?;
 
         shell.status(
             "Saved",
@@ -356,3 +419,189 @@ pub(crate) fn save_test_cases(
 
     Ok(acc)
 }
+
+/// Finds the memory limit in a problem page of AtCoder, Codeforces, or yukicoder.
+///
+/// The result is normalized like `1024 MiB`, since these sites mean mebibytes by "MB".
+fn find_memory_limit(html: &str) -> Option<String> {
+    const MARKERS: &[&str] = &["Memory Limit", "memory limit per test", "メモリ制限"];
+
+    let start = MARKERS
+        .iter()
+        .flat_map(|marker| html.find(marker).map(|i| i + marker.len()))
+        .min()?;
+
+    // Drop the HTML tags that may appear between the label and the value.
+    let text = strip_tags(&html[start..].chars().take(256).collect::<String>());
+
+    let text = text.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let value_end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (value, rest) = text.split_at(value_end);
+    let value = value.parse::<f64>().ok()?;
+
+    let unit = rest.trim_start().to_lowercase();
+    let unit = if unit.starts_with("kb") || unit.starts_with("kilobyte") {
+        "KiB"
+    } else if unit.starts_with("mb") || unit.starts_with("megabyte") {
+        "MiB"
+    } else if unit.starts_with("gb") || unit.starts_with("gigabyte") {
+        "GiB"
+    } else {
+        return None;
+    };
+
+    Some(format!("{} {}", value, unit))
+}
+
+/// Finds a statement like "absolute or relative error at most 10^{-6}" in the problem statement.
+///
+/// The rest of the page is not searched, since the sidebars and the notices of the contests may
+/// also mention errors.
+fn find_tolerance(html: &str) -> Option<CargoCompeteConfigTestMatch> {
+    // AtCoder, Codeforces, and yukicoder in this order.
+    const STATEMENT_SELECTORS: &[&str] = &["#task-statement", ".problem-statement", "#content"];
+
+    const MARKERS: &[&str] = &[
+        "absolute or relative error",
+        "relative or absolute error",
+        "absolute error",
+        "relative error",
+        "絶対誤差",
+        "相対誤差",
+    ];
+
+    let html = Html::parse_document(html);
+    let statement = STATEMENT_SELECTORS
+        .iter()
+        .find_map(|s| html.select(&Selector::parse(s).unwrap()).next())?;
+    let text = statement.text().collect::<String>().to_ascii_lowercase();
+
+    // Only the sentence that has the marker.
+    let text = &text[MARKERS.iter().flat_map(|m| text.find(m)).min()?..];
+    let text = &text[..text
+        .char_indices()
+        .find(|&(i, c)| c == '。' || (c == '.' && text[i + 1..].starts_with(char::is_whitespace)))
+        .map_or(text.len(), |(i, _)| i)];
+
+    let error = parse_power_of_ten(text).or_else(|| {
+        text.split_whitespace()
+            .map(|w| w.trim_end_matches(|c: char| !c.is_ascii_digit()))
+            .flat_map(|w| w.parse::<f64>())
+            .find(|&v| 0.0 < v && v < 1.0)
+    })?;
+
+    let relative = text.contains("relative") || text.contains("相対");
+    let absolute = text.contains("absolute") || text.contains("絶対");
+
+    Some(CargoCompeteConfigTestMatch::Float {
+        relative_error: Some(error).filter(|_| relative || !absolute),
+        absolute_error: Some(error).filter(|_| absolute || !relative),
+    })
+}
+
+/// Parses `10^{-6}`, `10^-6`, or `10^{−6}` (with U+2212).
+fn parse_power_of_ten(text: &str) -> Option<f64> {
+    let rest = &text[text.find("10^")? + 3..];
+    let rest = rest.trim_start_matches(['{', ' ']);
+    let rest = rest
+        .strip_prefix('-')
+        .or_else(|| rest.strip_prefix('−'))?
+        .trim_start();
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(rest, |i| &rest[..i]);
+    format!("1e-{}", digits).parse().ok()
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn find_memory_limit() {
+        assert_eq!(
+            Some("1024 MiB".to_owned()),
+            super::find_memory_limit("<p>\n  Time Limit: 2 sec / Memory Limit: 1024 MB\n</p>",),
+        );
+        assert_eq!(
+            Some("256 MiB".to_owned()),
+            super::find_memory_limit(
+                r#"<div class="memory-limit"><div class="property-title">memory limit per test</div>256 megabytes</div>"#,
+            ),
+        );
+        assert_eq!(
+            Some("512 MiB".to_owned()),
+            super::find_memory_limit("実行時間制限 : 1ケース 2.000秒 / メモリ制限 : 512 MB"),
+        );
+        assert_eq!(None, super::find_memory_limit("<p>no limits</p>"));
+    }
+
+    #[test]
+    fn find_tolerance() {
+        use crate::project::CargoCompeteConfigTestMatch::Float;
+
+        let float = |relative_error, absolute_error| -> _ {
+            Some(format!(
+                "{:?}",
+                Float {
+                    relative_error,
+                    absolute_error,
+                },
+            ))
+        };
+        let find = |html| super::find_tolerance(html).map(|m| format!("{:?}", m));
+
+        assert_eq!(
+            float(Some(1e-6), Some(1e-6)),
+            find(
+                "<div class=\"problem-statement\"><p>Your answer is considered correct if its \
+                 absolute or relative error does not exceed $$$10^{-6}$$$.</p></div>",
+            ),
+        );
+        assert_eq!(
+            float(Some(1e-9), Some(1e-9)),
+            find(
+                "<div id=\"task-statement\"><p>ジャッジの出力との絶対誤差または相対誤差が \
+                 <var>10^{-9}</var> 以下</p></div>",
+            ),
+        );
+        assert_eq!(
+            float(None, Some(1e-3)),
+            find("<div id=\"content\"><p>The absolute error must be at most 0.001.</p></div>"),
+        );
+        assert_eq!(
+            None,
+            find(
+                "<div id=\"task-statement\"><p>Print the answer.</p></div><div id=\"sidebar\">\
+                 <p>The absolute error must be at most 0.001 in Problem C.</p></div>",
+            ),
+        );
+        assert_eq!(
+            None,
+            find("<p>The absolute error must be at most 0.001.</p>")
+        );
+        assert_eq!(
+            None,
+            find(
+                "<div id=\"task-statement\"><p>Print the absolute error of the estimate. \
+                 It is guaranteed that 0.5 \\leq p.</p></div>",
+            ),
+        );
+    }
+}
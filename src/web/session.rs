@@ -0,0 +1,139 @@
+//! Requests for the pages `snowchains_core` does not retrieve, such as problem statements and
+//! submitted code.
+
+use crate::shell::Shell;
+use anyhow::{bail, Context as _};
+use cookie_store::CookieStore;
+use snowchains_core::web::{
+    Atcoder, AtcoderLoginCredentials, Codeforces, CodeforcesLoginCredentials, CookieStorage, Login,
+    LoginOutcome, PlatformKind,
+};
+use std::{borrow::BorrowMut as _, cell::RefCell, path::Path};
+use url::Url;
+
+/// HTTP session with the cookies that `snowchains_core` saved to `cookies_path`.
+pub(crate) struct Session<'a> {
+    client: reqwest::blocking::Client,
+    cookies_path: &'a Path,
+    cookie_store: CookieStore,
+}
+
+impl<'a> Session<'a> {
+    pub(crate) fn new(cookies_path: &'a Path) -> anyhow::Result<Self> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(crate::web::TIMEOUT)
+            .build()?;
+
+        Ok(Self {
+            client,
+            cookies_path,
+            cookie_store: load_cookie_store(cookies_path)?,
+        })
+    }
+
+    /// Gets the text at `url`.
+    ///
+    /// If AtCoder or Codeforces responds with 403 or redirects to the login page, this logs in
+    /// with `snowchains_core` and tries again.
+    pub(crate) fn get_text(&mut self, url: &Url, shell: &mut Shell) -> anyhow::Result<String> {
+        let mut res = self.get(url)?;
+
+        if requires_login(&res) {
+            if let Some(platform) = platform(url) {
+                login(platform, self.cookies_path, shell)?;
+                self.cookie_store = load_cookie_store(self.cookies_path)?;
+                res = self.get(url)?;
+            }
+        }
+
+        res.error_for_status()?.text().map_err(Into::into)
+    }
+
+    fn get(&self, url: &Url) -> reqwest::Result<reqwest::blocking::Response> {
+        let cookie = self
+            .cookie_store
+            .get_request_cookies(url)
+            .map(|c| format!("{}={}", c.name(), c.value()))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let mut req = self.client.get(url.clone());
+        if !cookie.is_empty() {
+            req = req.header(reqwest::header::COOKIE, cookie);
+        }
+        req.send()
+    }
+}
+
+fn load_cookie_store(cookies_path: &Path) -> anyhow::Result<CookieStore> {
+    if !cookies_path.exists() {
+        return Ok(CookieStore::default());
+    }
+
+    CookieStore::load_json(&*crate::fs::read(cookies_path)?)
+        .map_err(|e| anyhow::anyhow!(e))
+        .with_context(|| format!("could not load the cookies at `{}`", cookies_path.display()))
+}
+
+fn requires_login(res: &reqwest::blocking::Response) -> bool {
+    res.status() == reqwest::StatusCode::FORBIDDEN
+        || ["/login", "/enter"]
+            .iter()
+            .any(|p| res.url().path().starts_with(p))
+}
+
+fn platform(url: &Url) -> Option<PlatformKind> {
+    match url.host_str()? {
+        "atcoder.jp" => Some(PlatformKind::Atcoder),
+        "codeforces.com" => Some(PlatformKind::Codeforces),
+        _ => None,
+    }
+}
+
+/// Logs in to AtCoder or Codeforces, asking the username and the password.
+pub(crate) fn login(
+    platform: PlatformKind,
+    cookies_path: &Path,
+    shell: &mut Shell,
+) -> anyhow::Result<LoginOutcome> {
+    let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
+    let timeout = crate::web::TIMEOUT;
+
+    let shell = RefCell::new(shell.borrow_mut());
+
+    match platform {
+        PlatformKind::Atcoder => {
+            let credentials = AtcoderLoginCredentials {
+                username_and_password: &mut crate::web::credentials::username_and_password(
+                    &shell,
+                    "Username: ",
+                    "Password: ",
+                ),
+            };
+
+            Atcoder::exec(Login {
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        PlatformKind::Codeforces => {
+            let credentials = CodeforcesLoginCredentials {
+                username_and_password: &mut crate::web::credentials::username_and_password(
+                    &shell,
+                    "Handle/Email: ",
+                    "Password: ",
+                ),
+            };
+
+            Codeforces::exec(Login {
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        PlatformKind::Yukicoder => bail!("yukicoder does not need to login"),
+    }
+}
@@ -0,0 +1,316 @@
+//! Submission summaries in the JSON form of `AtcoderRetrieveSubmissionSummariesOutcome`.
+//!
+//! Codeforces and yukicoder submissions are converted into the same shape so that
+//! `retrieve submission-summaries` prints them uniformly.
+
+use crate::{
+    shell::Shell,
+    web::{
+        api::{codeforces_api, yukicoder_api},
+        credentials,
+    },
+};
+use anyhow::Context as _;
+use chrono::{DateTime, FixedOffset, TimeZone as _};
+use serde::{Deserialize, Serialize};
+use snowchains_core::web::{
+    Atcoder, AtcoderRetrieveSubmissionSummariesCredentials,
+    AtcoderRetrieveSubmissionSummariesTarget, CookieStorage, RetrieveSubmissionSummaries,
+};
+use std::{borrow::BorrowMut as _, cell::RefCell, path::Path};
+use url::Url;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct SubmissionSummaries {
+    pub(crate) summaries: Vec<SubmissionSummary>,
+}
+
+impl SubmissionSummaries {
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("should not fail")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct SubmissionSummary {
+    pub(crate) submission_time: DateTime<FixedOffset>,
+    pub(crate) task: SubmissionSummaryTask,
+    /// `None` for yukicoder.
+    pub(crate) user: Option<SubmissionSummaryUser>,
+    pub(crate) language: String,
+    pub(crate) score: String,
+    /// Empty for Codeforces.
+    pub(crate) code_size: String,
+    /// `"AC"`, `"WA"`, ..., or `"{n}/{m} {verdict}"` while judging.
+    pub(crate) status: String,
+    pub(crate) exec_time: Option<String>,
+    pub(crate) memory: Option<String>,
+    pub(crate) detail: Url,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct SubmissionSummaryTask {
+    pub(crate) display_name: String,
+    pub(crate) url: Url,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct SubmissionSummaryUser {
+    pub(crate) name: String,
+    pub(crate) url: Url,
+}
+
+/// Retrieves the submissions of the user in `contest` with `RetrieveSubmissionSummaries`.
+///
+/// The outcome only exposes `to_json`, so it is parsed back into `SubmissionSummaries`.
+pub(crate) fn retrieve_atcoder(
+    contest: &str,
+    cookies_path: &Path,
+    shell: &mut Shell,
+) -> anyhow::Result<SubmissionSummaries> {
+    let outcome = {
+        let shell = RefCell::new(shell.borrow_mut());
+
+        let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
+            username_and_password: &mut credentials::username_and_password(
+                &shell,
+                "Username: ",
+                "Password: ",
+            ),
+        };
+
+        Atcoder::exec(RetrieveSubmissionSummaries {
+            target: AtcoderRetrieveSubmissionSummariesTarget {
+                contest: contest.to_owned(),
+            },
+            credentials,
+            cookie_storage: CookieStorage::with_jsonl(cookies_path)?,
+            timeout: crate::web::TIMEOUT,
+            shell: &shell,
+        })?
+    };
+
+    serde_json::from_str(&outcome.to_json())
+        .with_context(|| "could not parse the submission summaries of AtCoder")
+}
+
+/// Retrieves the submissions of the user in `contest` with `contest.status` API.
+///
+/// Codeforces API does not provide the code sizes, so `code_size` is empty.
+pub(crate) fn retrieve_codeforces(
+    contest: &str,
+    shell: &mut Shell,
+) -> anyhow::Result<SubmissionSummaries> {
+    let (api_key, api_secret) = credentials::codeforces_api_key_and_secret(shell)?;
+    let handle = credentials::codeforces_handle(shell)?;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Submission {
+        id: u64,
+        creation_time_seconds: i64,
+        problem: Problem,
+        programming_language: String,
+        verdict: Option<String>,
+        time_consumed_millis: u64,
+        memory_consumed_bytes: u64,
+    }
+
+    #[derive(Deserialize)]
+    struct Problem {
+        index: String,
+        name: String,
+        #[serde(default)]
+        points: Option<f64>,
+    }
+
+    let submissions = codeforces_api::<Vec<Submission>>(
+        "contest.status",
+        &[("contestId", contest), ("handle", &handle)],
+        &api_key,
+        &api_secret,
+    )?;
+
+    let submissions = submissions
+        .into_iter()
+        .map(|submission| {
+            let task_url = format!(
+                "https://codeforces.com/contest/{}/problem/{}",
+                contest, submission.problem.index,
+            )
+            .parse()?;
+
+            let detail = format!(
+                "https://codeforces.com/contest/{}/submission/{}",
+                contest, submission.id,
+            )
+            .parse()?;
+
+            Ok(SubmissionSummary {
+                submission_time: FixedOffset::east(0)
+                    .timestamp(submission.creation_time_seconds, 0),
+                task: SubmissionSummaryTask {
+                    display_name: format!(
+                        "{} - {}",
+                        submission.problem.index, submission.problem.name,
+                    ),
+                    url: task_url,
+                },
+                user: Some(SubmissionSummaryUser {
+                    name: handle.clone(),
+                    url: format!("https://codeforces.com/profile/{}", handle).parse()?,
+                }),
+                language: submission.programming_language,
+                score: submission
+                    .problem
+                    .points
+                    .map(|p| p.to_string())
+                    .unwrap_or_default(),
+                code_size: "".to_owned(),
+                status: codeforces_status(submission.verdict.as_deref()),
+                exec_time: Some(format!("{} ms", submission.time_consumed_millis)),
+                memory: Some(format!("{} KB", submission.memory_consumed_bytes / 1024)),
+                detail,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(SubmissionSummaries {
+        summaries: submissions,
+    })
+}
+
+/// Converts a verdict of Codeforces API into the status that AtCoder shows.
+fn codeforces_status(verdict: Option<&str>) -> String {
+    match verdict {
+        None | Some("TESTING") => "WJ",
+        Some("OK") => "AC",
+        Some("WRONG_ANSWER") => "WA",
+        Some("TIME_LIMIT_EXCEEDED") => "TLE",
+        Some("MEMORY_LIMIT_EXCEEDED") => "MLE",
+        Some("RUNTIME_ERROR") => "RE",
+        Some("COMPILATION_ERROR") => "CE",
+        Some(verdict) => verdict,
+    }
+    .to_owned()
+}
+
+/// A submission to yukicoder made by `submit`.
+pub(crate) struct YukicoderSubmission<'a> {
+    /// Index for `package.metadata.cargo-compete.bin`.
+    pub(crate) problem: &'a str,
+    pub(crate) task_url: &'a Url,
+    pub(crate) id: u64,
+    pub(crate) submitted_at: DateTime<FixedOffset>,
+}
+
+/// Retrieves the submissions with yukicoder API.
+///
+/// yukicoder API cannot list the submissions of the user, so `submissions` are the ones recorded
+/// in the history. `user` is left `None`.
+pub(crate) fn retrieve_yukicoder(
+    submissions: &[YukicoderSubmission<'_>],
+    shell: &mut Shell,
+) -> anyhow::Result<SubmissionSummaries> {
+    let api_key = credentials::yukicoder_api_key(shell)?;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Submission {
+        id: u64,
+        #[serde(default)]
+        language_id: String,
+        result: String,
+        #[serde(default)]
+        time: Option<u64>,
+        #[serde(default)]
+        memory: Option<u64>,
+        #[serde(default)]
+        code_size: Option<u64>,
+    }
+
+    let submissions = submissions
+        .iter()
+        .map(|target| {
+            let submission =
+                yukicoder_api::<Submission>(&format!("submissions/{}", target.id), &api_key)?;
+
+            Ok(SubmissionSummary {
+                submission_time: target.submitted_at,
+                task: SubmissionSummaryTask {
+                    display_name: target.problem.to_owned(),
+                    url: target.task_url.clone(),
+                },
+                user: None,
+                language: submission.language_id,
+                score: "".to_owned(),
+                code_size: submission
+                    .code_size
+                    .map(|n| format!("{} Byte", n))
+                    .unwrap_or_default(),
+                status: submission.result,
+                exec_time: submission.time.map(|t| format!("{} ms", t)),
+                memory: submission.memory.map(|m| format!("{} KB", m)),
+                detail: format!("https://yukicoder.me/submissions/{}", submission.id).parse()?,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(SubmissionSummaries {
+        summaries: submissions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SubmissionSummaries, SubmissionSummary, SubmissionSummaryTask, SubmissionSummaryUser,
+    };
+    use chrono::DateTime;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_atcoder_submission_summaries() -> anyhow::Result<()> {
+        let json = r#"{"summaries":[{"submission_time":"2020-08-30T21:12:43+09:00","task":{"display_name":"A - Plus Minus","url":"https://atcoder.jp/contests/abc176/tasks/abc176_a"},"user":{"name":"qryxip","url":"https://atcoder.jp/users/qryxip"},"language":"Rust (1.42.0)","score":"100","code_size":"247 Byte","status":"2/9 WA","exec_time":null,"memory":null,"detail":"https://atcoder.jp/contests/abc176/submissions/16366470"}]}"#;
+
+        let summaries = serde_json::from_str::<SubmissionSummaries>(json)?;
+
+        assert_eq!(
+            SubmissionSummaries {
+                summaries: vec![SubmissionSummary {
+                    submission_time: DateTime::parse_from_rfc3339("2020-08-30T21:12:43+09:00")?,
+                    task: SubmissionSummaryTask {
+                        display_name: "A - Plus Minus".to_owned(),
+                        url: "https://atcoder.jp/contests/abc176/tasks/abc176_a".parse()?,
+                    },
+                    user: Some(SubmissionSummaryUser {
+                        name: "qryxip".to_owned(),
+                        url: "https://atcoder.jp/users/qryxip".parse()?,
+                    }),
+                    language: "Rust (1.42.0)".to_owned(),
+                    score: "100".to_owned(),
+                    code_size: "247 Byte".to_owned(),
+                    status: "2/9 WA".to_owned(),
+                    exec_time: None,
+                    memory: None,
+                    detail: "https://atcoder.jp/contests/abc176/submissions/16366470".parse()?,
+                }],
+            },
+            summaries,
+        );
+        assert_eq!(json, summaries.to_json());
+        Ok(())
+    }
+
+    #[test]
+    fn codeforces_status() {
+        let status = super::codeforces_status;
+
+        assert_eq!("WJ", status(None));
+        assert_eq!("WJ", status(Some("TESTING")));
+        assert_eq!("AC", status(Some("OK")));
+        assert_eq!("WA", status(Some("WRONG_ANSWER")));
+        assert_eq!("TLE", status(Some("TIME_LIMIT_EXCEEDED")));
+        assert_eq!("CHALLENGED", status(Some("CHALLENGED")));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{
+    shell::Shell,
+    web::{
+        api::{codeforces_api, yukicoder_api},
+        credentials,
+    },
+};
+use anyhow::{bail, Context as _};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Deserialize;
+use std::{thread, time::Duration};
+use termcolor::Color;
+use url::Url;
+
+const INTERVAL: Duration = Duration::from_secs(2);
+
+/// Watches the submission with `contest.status` API, until it gets a final verdict.
+///
+/// `submission_url` is a URL such as `https://codeforces.com/contest/{contest}/submission/{id}`.
+/// If it is absent, the latest submission of the user in `contest` is watched. Returns the
+/// verdict.
+pub(crate) fn watch_codeforces(
+    contest: &str,
+    submission_url: Option<&Url>,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    let mut submission_id = submission_url
+        .map(parse_codeforces_submission_id)
+        .transpose()?;
+
+    let (api_key, api_secret) = credentials::codeforces_api_key_and_secret(shell)?;
+    let handle = credentials::codeforces_handle(shell)?;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Submission {
+        id: u64,
+        problem: Problem,
+        verdict: Option<String>,
+        passed_test_count: u64,
+        time_consumed_millis: u64,
+        memory_consumed_bytes: u64,
+    }
+
+    #[derive(Deserialize)]
+    struct Problem {
+        index: String,
+    }
+
+    let progress = progress(shell);
+
+    let submission = loop {
+        // The submission may not be listed yet, or may be behind the ones submitted after it.
+        let submissions = codeforces_api::<Vec<Submission>>(
+            "contest.status",
+            &[
+                ("contestId", contest),
+                ("handle", &handle),
+                ("from", "1"),
+                ("count", "10"),
+            ],
+            &api_key,
+            &api_secret,
+        )?;
+
+        let submission = match submission_id {
+            Some(id) => submissions.into_iter().find(|s| s.id == id),
+            None => submissions.into_iter().next(),
+        };
+
+        match submission {
+            Some(submission) => {
+                if !matches!(submission.verdict.as_deref(), None | Some("TESTING")) {
+                    break submission;
+                }
+                submission_id = Some(submission.id);
+                progress.set_prefix(&format!("#{}", submission.id));
+                progress.set_message(&format!(
+                    "{}: Running on test {}",
+                    submission.problem.index,
+                    submission.passed_test_count + 1,
+                ));
+            }
+            None if submission_id.is_some() => progress.set_message("Waiting for the submission"),
+            None => bail!("no submissions by `{}` in `{}`", handle, contest),
+        }
+
+        thread::sleep(INTERVAL);
+    };
+
+    progress.finish_and_clear();
+
+    let verdict = submission.verdict.unwrap_or_default();
+    shell.status_with_color(
+        &verdict,
+        format!(
+            "#{} {} ({} ms, {} KiB, passed {}) https://codeforces.com/contest/{}/submission/{}",
+            submission.id,
+            submission.problem.index,
+            submission.time_consumed_millis,
+            submission.memory_consumed_bytes / 1024,
+            submission.passed_test_count,
+            contest,
+            submission.id,
+        ),
+        if verdict == "OK" {
+            Color::Green
+        } else {
+            Color::Yellow
+        },
+    )?;
+    Ok(verdict)
+}
+
+/// Watches the submission with yukicoder API, until it gets a final verdict.
+///
+/// `submission` is an ID or a URL such as `https://yukicoder.me/submissions/{id}`. Returns the
+/// verdict.
+pub(crate) fn watch_yukicoder(submission: &str, shell: &mut Shell) -> anyhow::Result<String> {
+    let submission_id = parse_yukicoder_submission_id(submission)?;
+    let api_key = credentials::yukicoder_api_key(shell)?;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Submission {
+        id: u64,
+        problem_id: u64,
+        result: String,
+    }
+
+    let progress = progress(shell);
+    progress.set_prefix(&format!("#{}", submission_id));
+
+    let submission = loop {
+        let submission =
+            yukicoder_api::<Submission>(&format!("submissions/{}", submission_id), &api_key)?;
+
+        if is_yukicoder_judging(&submission.result) {
+            progress.set_message(&format!(
+                "problem ID {}: {}",
+                submission.problem_id, submission.result,
+            ));
+        } else {
+            break submission;
+        }
+
+        thread::sleep(INTERVAL);
+    };
+
+    progress.finish_and_clear();
+
+    shell.status_with_color(
+        &submission.result,
+        format!(
+            "#{} (problem ID {}) https://yukicoder.me/submissions/{}",
+            submission.id, submission.problem_id, submission.id,
+        ),
+        if submission.result == "AC" {
+            Color::Green
+        } else {
+            Color::Yellow
+        },
+    )?;
+    Ok(submission.result)
+}
+
+/// Parses `https://codeforces.com/{contest|gym}/{contest}/submission/{id}`.
+fn parse_codeforces_submission_id(url: &Url) -> anyhow::Result<u64> {
+    match *url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+    {
+        ["contest", _, "submission", id] | ["gym", _, "submission", id] => id
+            .parse()
+            .with_context(|| format!("invalid submission ID: `{}`", id)),
+        _ => bail!("`{}` is not a URL of a Codeforces submission", url),
+    }
+}
+
+pub(crate) fn parse_yukicoder_submission_id(submission: &str) -> anyhow::Result<u64> {
+    if let Ok(id) = submission.parse() {
+        return Ok(id);
+    }
+
+    let url = submission
+        .parse::<Url>()
+        .with_context(|| format!("`{}` is neither a submission ID nor a URL", submission))?;
+
+    match *url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+    {
+        ["submissions", id] | ["submissions", id, ""] => id
+            .parse()
+            .with_context(|| format!("invalid submission ID: `{}`", id)),
+        _ => bail!("`{}` is not a URL of a yukicoder submission", url),
+    }
+}
+
+fn is_yukicoder_judging(result: &str) -> bool {
+    let result = result.to_lowercase();
+    result.is_empty() || result.starts_with("wj") || result.starts_with("judg")
+}
+
+/// Progress of a submission, with its ID as the prefix and the elapsed time.
+fn progress(shell: &Shell) -> ProgressBar {
+    let progress = ProgressBar::new_spinner();
+    progress.set_draw_target(shell.progress_draw_target());
+    progress.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner} {prefix:.bold} [{elapsed}] {wide_msg}"),
+    );
+    progress.enable_steady_tick(100);
+    progress.set_message("Waiting for the submission");
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_codeforces_submission_id() -> anyhow::Result<()> {
+        assert_eq!(
+            123_456,
+            super::parse_codeforces_submission_id(
+                &"https://codeforces.com/contest/1234/submission/123456".parse()?,
+            )?,
+        );
+        assert!(super::parse_codeforces_submission_id(
+            &"https://codeforces.com/contest/1234/my".parse()?,
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_yukicoder_submission_id() -> anyhow::Result<()> {
+        assert_eq!(
+            123_456,
+            super::parse_yukicoder_submission_id("https://yukicoder.me/submissions/123456")?,
+        );
+        assert_eq!(123_456, super::parse_yukicoder_submission_id("123456")?);
+        assert!(
+            super::parse_yukicoder_submission_id("https://yukicoder.me/problems/no/1").is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn is_yukicoder_judging() {
+        assert!(super::is_yukicoder_judging("WJ"));
+        assert!(super::is_yukicoder_judging("Judge"));
+        assert!(!super::is_yukicoder_judging("AC"));
+        assert!(!super::is_yukicoder_judging("TLE"));
+    }
+}